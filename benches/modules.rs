@@ -0,0 +1,155 @@
+use adventofcode2019rust::asteroids::Region;
+use adventofcode2019rust::code;
+use adventofcode2019rust::fuel;
+use adventofcode2019rust::grid::{Grid, Route};
+use adventofcode2019rust::input;
+use adventofcode2019rust::orbits::Universe;
+use adventofcode2019rust::program::{self, Program};
+use adventofcode2019rust::sif::Image;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+
+fn data(day: u32) -> &'static str {
+    input::bundled(day).expect("bundled input")
+}
+
+// The real day 6 map takes seconds per run, so use complete binary trees of
+// a few hundred objects instead.
+fn tree_universe(depth: u32) -> Universe {
+    let lines = (1..(1 << depth) - 1)
+        .map(|n| format!("N{})N{}\nN{})N{}", n, 2 * n, n, 2 * n + 1))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    Universe::from(lines.as_str())
+}
+
+fn bench_fuel(c: &mut Criterion) {
+    let masses: Vec<i64> = data(1)
+        .split_whitespace()
+        .map(|line| line.parse().unwrap())
+        .collect();
+
+    c.bench_function("fuel/total_fuel_needed_for_mass", |b| {
+        b.iter(|| {
+            masses
+                .iter()
+                .map(|mass| fuel::total_fuel_needed_for_mass(black_box(*mass)))
+                .sum::<i64>()
+        })
+    });
+
+    let table = fuel::FuelTable::new(fuel::DEFAULT_TABLE_SIZE);
+
+    c.bench_function("fuel/FuelTable::total_fuel", |b| {
+        b.iter(|| table.total_fuel_for(black_box(&masses)))
+    });
+}
+
+fn bench_grid(c: &mut Criterion) {
+    let routes: Vec<Route> = data(3)
+        .split_whitespace()
+        .map(|line| line.parse().unwrap())
+        .collect();
+    let grid = Grid::from(&routes);
+
+    c.bench_function("grid/intersection", |b| b.iter(|| grid.intersection()));
+}
+
+fn bench_code(c: &mut Criterion) {
+    let mut group = c.benchmark_group("code/find_candidates");
+    group.sample_size(10);
+
+    for range in &[273_025..=767_253, 357_253..=892_942] {
+        let label = format!("{}-{}", range.start(), range.end());
+
+        group.bench_with_input(
+            BenchmarkId::new("find_candidates", &label),
+            range,
+            |b, range| b.iter(|| code::find_candidates(range.clone())),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("find_candidates2", &label),
+            range,
+            |b, range| b.iter(|| code::find_candidates2(range.clone())),
+        );
+    }
+
+    group.finish();
+
+    let mut group = c.benchmark_group("code/is_candidate");
+
+    group.bench_function("is_candidate", |b| {
+        b.iter(|| code::is_candidate(black_box(455_567)))
+    });
+    group.bench_function("is_candidate2", |b| {
+        b.iter(|| code::is_candidate2(black_box(455_567)))
+    });
+
+    group.finish();
+}
+
+fn bench_orbits(c: &mut Criterion) {
+    let mut group = c.benchmark_group("orbits/count_indirect_orbits");
+    group.sample_size(10);
+
+    for depth in &[6, 8] {
+        let universe = tree_universe(*depth);
+
+        group.bench_with_input(
+            BenchmarkId::from_parameter(universe.count_objects()),
+            &universe,
+            |b, universe| b.iter(|| universe.count_indirect_orbits()),
+        );
+    }
+
+    group.finish();
+}
+
+fn bench_program(c: &mut Criterion) {
+    let code = program::parse_program(data(9)).unwrap();
+
+    let mut group = c.benchmark_group("program/run_d09");
+    group.sample_size(10);
+
+    for input in &[1, 2] {
+        group.bench_with_input(BenchmarkId::from_parameter(input), input, |b, input| {
+            b.iter(|| {
+                let mut program = Program::new(&code, &[*input]);
+                program.run();
+                program.output()
+            })
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_sif(c: &mut Criterion) {
+    let digits = data(8).trim().chars().map(|ch| ch.to_digit(10).unwrap());
+    let image = Image::new(25, 6, digits);
+
+    c.bench_function("sif/visible", |b| b.iter(|| image.visible()));
+}
+
+fn bench_asteroids(c: &mut Criterion) {
+    let region = Region::from(data(10));
+
+    let mut group = c.benchmark_group("asteroids");
+    group.sample_size(10);
+    group.bench_function("max_visible_from_count", |b| {
+        b.iter(|| region.max_visible_from_count().1)
+    });
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_fuel,
+    bench_grid,
+    bench_code,
+    bench_orbits,
+    bench_program,
+    bench_sif,
+    bench_asteroids
+);
+criterion_main!(benches);
@@ -0,0 +1,23 @@
+#![no_main]
+use adventofcode2019rust::reference;
+use libfuzzer_sys::fuzz_target;
+use std::sync::Once;
+
+static QUIET_PANICS: Once = Once::new();
+
+// Small words make it likely that the fuzzer finds real opcodes and modes.
+fuzz_target!(|data: (Vec<i16>, Vec<i16>)| {
+    // `Program` panics on programs it can't run, which `compare` treats as a
+    // fault. Only a divergence should make it out to the fuzzer.
+    QUIET_PANICS.call_once(|| std::panic::set_hook(Box::new(|_| {})));
+
+    let code = data.0.into_iter().map(i64::from).collect::<Vec<_>>();
+    let inputs = data.1.into_iter().map(i64::from).collect::<Vec<_>>();
+
+    if let Err(divergence) = reference::compare(&code, &inputs, 10_000) {
+        eprintln!("{}", divergence);
+        eprintln!("code: {:?}", code);
+        eprintln!("inputs: {:?}", inputs);
+        panic!("interpreters diverged");
+    }
+});
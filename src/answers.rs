@@ -0,0 +1,342 @@
+use crate::solution::{self, Part};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+#[derive(Debug, PartialEq)]
+pub struct ParseAnswersError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl Error for ParseAnswersError {}
+
+impl fmt::Display for ParseAnswersError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+// Trailing whitespace on a line is never significant, so rendered answers (day 8)
+// compare equal however their rows were padded.
+fn normalize(answer: &str) -> String {
+    answer
+        .trim_end()
+        .lines()
+        .map(str::trim_end)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn unescape(value: &str) -> Result<String, String> {
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars();
+
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            result.push(ch);
+            continue;
+        }
+
+        match chars.next() {
+            Some('n') => result.push('\n'),
+            Some('\\') => result.push('\\'),
+            Some(other) => return Err(format!("Unknown escape \\{}", other)),
+            None => return Err("Answer ends with a lone \\".to_string()),
+        }
+    }
+
+    Ok(result)
+}
+
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Status {
+    Correct,
+    Mismatch { expected: String },
+    Missing,
+    Failed(String),
+}
+
+/**
+ * Expected answers, read from lines of `<day> <part> <answer>`.
+ */
+#[derive(Debug, Default)]
+pub struct Answers(HashMap<(u32, Part), String>);
+
+impl FromStr for Answers {
+    type Err = ParseAnswersError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut answers = HashMap::new();
+
+        for (i, line) in text.lines().enumerate() {
+            let error = |message: String| ParseAnswersError {
+                line: i + 1,
+                message,
+            };
+
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut fields = line.splitn(3, char::is_whitespace);
+
+            let day = fields
+                .next()
+                .and_then(|day| day.parse::<u32>().ok())
+                .ok_or_else(|| error(format!("Invalid day in {:?}", line)))?;
+
+            let part = match fields.next() {
+                Some("1") => Part::One,
+                Some("2") => Part::Two,
+                _ => return Err(error(format!("Invalid part in {:?}", line))),
+            };
+
+            let value = fields
+                .next()
+                .map(str::trim_start)
+                .filter(|value| !value.is_empty())
+                .ok_or_else(|| error(format!("Missing answer in {:?}", line)))?;
+
+            if answers
+                .insert((day, part), unescape(value).map_err(error)?)
+                .is_some()
+            {
+                return Err(error(format!(
+                    "Day {} part {} is listed twice",
+                    day,
+                    part.number()
+                )));
+            }
+        }
+
+        Ok(Answers(answers))
+    }
+}
+
+impl fmt::Display for Answers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut keys = self.0.keys().collect::<Vec<_>>();
+        keys.sort_by_key(|(day, part)| (*day, part.number()));
+
+        for key in keys {
+            writeln!(f, "{} {} {}", key.0, key.1.number(), escape(&self.0[key]))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Answers {
+    /**
+     * The answers for the inputs bundled in `data/`.
+     */
+    pub fn bundled() -> Self {
+        include_str!("../answers/bundled.txt")
+            .parse()
+            .expect("answers/bundled.txt should parse")
+    }
+
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let text = fs::read_to_string(path)
+            .map_err(|e| format!("Could not read {}: {}", path.display(), e))?;
+
+        Ok(text
+            .parse()
+            .map_err(|e| format!("{}: {}", path.display(), e))?)
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn get(&self, day: u32, part: Part) -> Option<&str> {
+        self.0.get(&(day, part)).map(String::as_str)
+    }
+
+    pub fn insert(&mut self, day: u32, part: Part, answer: &str) {
+        self.0.insert((day, part), answer.to_string());
+    }
+
+    pub fn retain(&mut self, keep: impl Fn(u32, Part) -> bool) {
+        self.0.retain(|(day, part), _| keep(*day, *part));
+    }
+
+    pub fn check(&self, day: u32, part: Part, answer: &solution::Answer) -> Status {
+        let actual = match answer {
+            Ok(actual) => actual,
+            Err(e) => return Status::Failed(e.to_string()),
+        };
+
+        match self.get(day, part) {
+            None => Status::Missing,
+            Some(expected) if normalize(expected) == normalize(actual) => Status::Correct,
+            Some(expected) => Status::Mismatch {
+                expected: expected.to_string(),
+            },
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Verification {
+    pub day: u32,
+    pub part: Part,
+    pub actual: Option<String>,
+    pub status: Status,
+}
+
+#[derive(Debug, Default)]
+pub struct Report {
+    pub results: Vec<Verification>,
+}
+
+impl Report {
+    fn with_status(&self, f: impl Fn(&Status) -> bool) -> Vec<&Verification> {
+        self.results.iter().filter(|v| f(&v.status)).collect()
+    }
+
+    pub fn correct(&self) -> Vec<&Verification> {
+        self.with_status(|status| *status == Status::Correct)
+    }
+
+    pub fn mismatches(&self) -> Vec<&Verification> {
+        self.with_status(|status| matches!(status, Status::Mismatch { .. }))
+    }
+
+    pub fn missing(&self) -> Vec<&Verification> {
+        self.with_status(|status| *status == Status::Missing)
+    }
+
+    pub fn failures(&self) -> Vec<&Verification> {
+        self.with_status(|status| matches!(status, Status::Failed(_)))
+    }
+
+    /**
+     * Missing answers are reported, but only wrong answers and failures count
+     * against a run.
+     */
+    pub fn is_ok(&self) -> bool {
+        self.mismatches().is_empty() && self.failures().is_empty()
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} correct, {} wrong, {} failed, {} without an expected answer",
+            self.correct().len(),
+            self.mismatches().len(),
+            self.failures().len(),
+            self.missing().len()
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_answers() {
+        let answers: Answers = "# comment\n\n1 1 658\n1 2  970\n8 2 # \\n #\\\\\n"
+            .parse()
+            .unwrap();
+
+        assert_eq!(answers.len(), 3);
+        assert_eq!(answers.get(1, Part::One), Some("658"));
+        assert_eq!(answers.get(1, Part::Two), Some("970"));
+        assert_eq!(answers.get(8, Part::Two), Some("# \n #\\"));
+        assert_eq!(answers.get(2, Part::One), None);
+
+        assert_eq!(answers.to_string().parse::<Answers>().unwrap().0, answers.0);
+    }
+
+    #[test]
+    fn test_parse_answers_errors() {
+        let line = |text: &str| text.parse::<Answers>().unwrap_err().line;
+
+        assert_eq!(line("1 1 658\nx 1 970"), 2);
+        assert_eq!(line("1 3 658"), 1);
+        assert_eq!(line("\n\n1 1"), 3);
+        assert_eq!(line("1 1 a\\tb"), 1);
+        assert_eq!(line("1 1 658\n1 1 659"), 2);
+    }
+
+    #[test]
+    fn test_check() {
+        let mut answers = Answers::default();
+        answers.insert(1, Part::One, "658");
+        answers.insert(8, Part::Two, "#\n #");
+
+        assert_eq!(
+            answers.check(1, Part::One, &Ok("658".to_string())),
+            Status::Correct
+        );
+        assert_eq!(
+            answers.check(1, Part::One, &Ok("659".to_string())),
+            Status::Mismatch {
+                expected: "658".to_string()
+            }
+        );
+        assert_eq!(
+            answers.check(1, Part::Two, &Ok("970".to_string())),
+            Status::Missing
+        );
+        assert_eq!(
+            answers.check(1, Part::Two, &Err("bad input".into())),
+            Status::Failed("bad input".to_string())
+        );
+        assert_eq!(
+            answers.check(8, Part::Two, &Ok("#  \n #\n".to_string())),
+            Status::Correct
+        );
+    }
+
+    #[test]
+    fn test_report() {
+        let mut answers = Answers::default();
+        answers.insert(4, Part::One, "911");
+        answers.insert(1, Part::One, "658");
+
+        let mut report = Report::default();
+
+        for (day, part, actual) in &[
+            (1, Part::One, "658"),
+            (4, Part::One, "910"),
+            (4, Part::Two, "598"),
+        ] {
+            report.results.push(Verification {
+                day: *day,
+                part: *part,
+                actual: Some(actual.to_string()),
+                status: answers.check(*day, *part, &Ok(actual.to_string())),
+            });
+        }
+
+        assert_eq!(report.correct().len(), 1);
+        assert_eq!(report.mismatches().len(), 1);
+        assert_eq!(report.mismatches()[0].actual.as_deref(), Some("910"));
+        assert_eq!(report.missing().len(), 1);
+        assert_eq!(report.missing()[0].day, 4);
+        assert!(!report.is_ok());
+        assert_eq!(
+            report.to_string(),
+            "1 correct, 1 wrong, 0 failed, 1 without an expected answer"
+        );
+    }
+}
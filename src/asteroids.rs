@@ -1,8 +1,9 @@
-use crate::grid::Coordinate;
-use num::{Rational, Zero};
+use crate::grid::{compare_clockwise, Coordinate, ReadingOrder};
+use crate::grid2d::{Grid2D, ParseGridError};
+use crate::solution::{Answer, Solution};
+use num::{Integer, Rational, Zero};
 use rayon::prelude::*;
-use std::cmp::{Ord, Ordering, PartialOrd};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::convert::TryInto;
 use std::error::Error;
 
@@ -27,21 +28,6 @@ impl Coordinate {
     }
 }
 
-impl Ord for Coordinate {
-    fn cmp(&self, other: &Self) -> Ordering {
-        if self.eq(&other) {
-            return Ordering::Equal;
-        }
-        self.angle().partial_cmp(&other.angle()).unwrap()
-    }
-}
-
-impl PartialOrd for Coordinate {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(&other))
-    }
-}
-
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct InvalidCoordinateError;
 
@@ -61,17 +47,15 @@ struct Slope {
 
 impl Slope {
     fn new(from: &Coordinate, to: &Coordinate) -> Self {
-        let y_diff = to.y - from.y;
-        let y_diff_is_neg = y_diff < 0;
-
-        let x_diff = to.x - from.x;
-        let x_diff_is_neg = x_diff < 0;
+        let diff = to - from;
+        let y_diff_is_neg = diff.y < 0;
+        let x_diff_is_neg = diff.x < 0;
 
         // (y, x)
-        let (mut numer, mut denom) = if to.x - from.x == Zero::zero() {
+        let (mut numer, mut denom) = if diff.x == Zero::zero() {
             (1, 0)
         } else {
-            let rational = Rational::new(to.y - from.y, to.x - from.x);
+            let rational = Rational::new(diff.y, diff.x);
 
             let numer = *rational.numer();
             let denom = *rational.denom();
@@ -92,58 +76,101 @@ impl Slope {
     }
 }
 
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum Rotation {
+    #[default]
+    Clockwise,
+    CounterClockwise,
+}
+
+/**
+ * The order a rotating laser at `from` vaporizes the rest of the region: each turn it
+ * hits the nearest asteroid left in every direction. The whole order is worked out up
+ * front, so `nth` is O(1).
+ */
 #[derive(Debug, Default)]
 pub struct Obliteration {
-    region: Region,
     from: Coordinate,
-    visible_from: Vec<Coordinate>,
+    order: Vec<Coordinate>,
+    next: usize,
 }
 
 impl Obliteration {
+    /**
+     * Starts pointing straight up and turns clockwise.
+     */
     pub fn new(
         region: &Region,
         from: &Coordinate,
     ) -> Result<Self, InvalidCoordinateError> {
-        if !region.contains(from) {
+        Self::with_rotation(region, from, &Coordinate::new(0, -1), Rotation::Clockwise)
+    }
+
+    /**
+     * Starts pointing along `start`, a direction such as (1, 0) for right, and turns
+     * the given way. Asteroids straight along `start` go first.
+     */
+    pub fn with_rotation(
+        region: &Region,
+        from: &Coordinate,
+        start: &Coordinate,
+        rotation: Rotation,
+    ) -> Result<Self, InvalidCoordinateError> {
+        if !region.contains(from) || *start == Coordinate::default() {
             return Err(InvalidCoordinateError);
         }
 
-        Ok(Obliteration {
-            region: region.clone(),
-            from: from.clone(),
-            ..Default::default()
-        })
-    }
+        let mut queues: HashMap<Coordinate, Vec<Coordinate>> = HashMap::new();
 
-    fn populate_visible_from(&mut self) -> bool {
-        if !self.visible_from.is_empty() {
-            return false;
+        for (towards, c) in region.in_sight(from) {
+            queues.entry(towards).or_default().push(c.clone());
         }
 
-        let mut visible_from = self
-            .region
-            .visible_from(&self.from)
-            .unwrap() // checked `from` is valid already in constructor.
-            .into_par_iter()
-            .map(|c| Coordinate {
-                x: c.x - self.from.x,
-                y: c.y - self.from.y,
-            })
-            .collect::<Vec<_>>();
+        // Counterclockwise is clockwise in the mirror image.
+        let mirror = |c: &Coordinate| match rotation {
+            Rotation::Clockwise => c.clone(),
+            Rotation::CounterClockwise => Coordinate::new(-c.x, c.y),
+        };
+
+        let mut queues = queues.into_iter().collect::<Vec<_>>();
+        queues.sort_by(|(a, _), (b, _)| {
+            compare_clockwise(&mirror(start), &mirror(a), &mirror(b))
+        });
 
-        visible_from.sort();
+        for (_, queue) in queues.iter_mut() {
+            queue.sort_by_key(|c| (c - from).manhattan_distance());
+        }
 
-        visible_from = visible_from
-            .into_par_iter()
-            .map(|c| Coordinate {
-                x: c.x + self.from.x,
-                y: c.y + self.from.y,
+        let longest = queues
+            .iter()
+            .map(|(_, queue)| queue.len())
+            .max()
+            .unwrap_or(0);
+
+        let order = (0..longest)
+            .flat_map(|turn| {
+                queues
+                    .iter()
+                    .filter_map(move |(_, queue)| queue.get(turn).cloned())
             })
-            .collect::<Vec<_>>();
+            .collect();
+
+        Ok(Obliteration {
+            from: from.clone(),
+            order,
+            next: 0,
+        })
+    }
 
-        self.visible_from = visible_from;
+    pub fn from(&self) -> &Coordinate {
+        &self.from
+    }
 
-        true
+    /**
+     * Everything the laser will vaporize, in order.
+     */
+    pub fn order(&self) -> &[Coordinate] {
+        &self.order
     }
 }
 
@@ -151,19 +178,65 @@ impl Iterator for Obliteration {
     type Item = Coordinate;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.visible_from.is_empty() {
-            self.populate_visible_from();
-        }
+        let result = self.order.get(self.next).cloned();
+        self.next = self.order.len().min(self.next + 1);
+        result
+    }
 
-        if self.visible_from.is_empty() {
-            return None;
-        }
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.next = self.order.len().min(self.next.saturating_add(n));
+        self.next()
+    }
 
-        let result = self.visible_from.remove(0);
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let left = self.order.len().saturating_sub(self.next);
+        (left, Some(left))
+    }
+}
+
+impl ExactSizeIterator for Obliteration {}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Cell {
+    Empty,
+    Asteroid,
+    // An asteroid with the monitoring station on it.
+    Station,
+    // Blocks the view but can't be vaporized.
+    Obstacle,
+}
 
-        self.region.remove(&result);
+/**
+ * Which character stands for which kind of cell in a map.
+ */
+#[derive(Clone, Debug, PartialEq)]
+pub struct CellMap {
+    pub empty: char,
+    pub asteroid: char,
+    pub station: char,
+    pub obstacle: char,
+}
 
-        Some(result)
+impl Default for CellMap {
+    fn default() -> Self {
+        CellMap {
+            empty: '.',
+            asteroid: '#',
+            station: 'X',
+            obstacle: '@',
+        }
+    }
+}
+
+impl CellMap {
+    pub fn cell(&self, ch: char) -> Option<Cell> {
+        match ch {
+            _ if ch == self.empty => Some(Cell::Empty),
+            _ if ch == self.asteroid => Some(Cell::Asteroid),
+            _ if ch == self.station => Some(Cell::Station),
+            _ if ch == self.obstacle => Some(Cell::Obstacle),
+            _ => None,
+        }
     }
 }
 
@@ -172,11 +245,50 @@ pub struct Region {
     pub coordinates: HashSet<Coordinate>,
     pub width: usize,
     pub height: usize,
+    // Also in `coordinates`.
+    pub station: Option<Coordinate>,
+    pub obstacles: HashSet<Coordinate>,
+    // The symbols the region was drawn with, for rendering it again.
+    pub cells: CellMap,
 }
 
 impl Region {
-    fn is_asteroid(ch: char) -> bool {
-        ch == '#'
+    /**
+     * Reads a map drawn with the default `CellMap`.
+     */
+    pub fn parse(text: &str) -> Result<Self, ParseGridError> {
+        Self::parse_with(text, &CellMap::default())
+    }
+
+    /**
+     * Reads a map, failing on ragged rows, characters `cells` doesn't know and any
+     * station after the first. Unless `cells` uses whitespace for a cell, whitespace
+     * at the end of a row is ignored.
+     */
+    pub fn parse_with(text: &str, cells: &CellMap) -> Result<Self, ParseGridError> {
+        let symbols = [cells.empty, cells.asteroid, cells.station, cells.obstacle];
+
+        let text = if symbols.iter().any(|ch| ch.is_whitespace()) {
+            text.to_string()
+        } else {
+            text.lines()
+                .map(str::trim_end)
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+
+        // A second station is as unexpected as an unknown character.
+        let seen_station = std::cell::Cell::new(false);
+
+        let map = Grid2D::parse_with(&text, |ch| match cells.cell(ch) {
+            Some(Cell::Station) if seen_station.replace(true) => None,
+            cell => cell,
+        })?;
+
+        Ok(Region {
+            cells: cells.clone(),
+            ..Self::from(&map)
+        })
     }
 
     pub fn len(&self) -> usize {
@@ -212,29 +324,29 @@ impl Region {
         }
 
         let slope = Slope::new(from, to);
+        let step = Coordinate::new(slope.x, slope.y);
 
-        let Coordinate { mut x, mut y } = from;
+        let mut coord = from.clone();
 
         let mut coordinates = Vec::new();
 
-        while self.could_contain(&Coordinate { x, y }) {
-            // println!("    coord: {:?}", Coordinate { x, y });
+        while self.could_contain(&coord) {
+            // println!("    coord: {:?}", coord);
 
-            coordinates.push(Coordinate { x, y });
+            coordinates.push(coord.clone());
 
-            if x == to.x && y == to.y {
+            if coord == *to {
                 break;
             }
 
-            x += slope.x;
-            y += slope.y;
+            coord = coord + &step;
         }
 
         // println!("    coordinates before: {:?}", coordinates);
 
         coordinates = coordinates
             .into_iter()
-            .filter(|coord| self.contains(&coord))
+            .filter(|coord| self.contains(&coord) || self.obstacles.contains(coord))
             .collect::<Vec<_>>();
 
         // println!("    coordinates after: {:?}", coordinates);
@@ -263,85 +375,166 @@ impl Region {
             return Err(InvalidCoordinateError);
         }
 
-        let mut visible = Vec::new();
+        // Only the nearest asteroid in each direction can be seen.
+        let mut nearest: HashMap<Coordinate, &Coordinate> = HashMap::new();
 
-        for c in &self.coordinates {
-            if coord == c {
-                continue;
-            }
-            // println!("  {:?} checking against: {:?}", coord, c);
-            if let Ok(_can_see) = self.can_see(&coord, &c) {
-                if _can_see {
-                    visible.push(c.clone());
-                // println!("    CAN SEE!");
-                // can_see_for_coord += 1;
-                } else {
-                    // println!("    can't see.");
-                }
+        for (towards, c) in self.in_sight(coord) {
+            let nearest = nearest.entry(towards).or_insert(c);
+
+            if (c - coord).manhattan_distance() < (*nearest - coord).manhattan_distance()
+            {
+                *nearest = c;
             }
         }
 
-        // println!("can_see from: {:?}, to: {:?}", from, to);
-        // let full_path = self.derive_full_path(from, to)?;
-        // println!("can_see full_path: {:?}", full_path);
-        // Ok(full_path.len() == 2 && full_path[1] == *to)
+        Ok(nearest.into_values().cloned().collect())
+    }
 
-        Ok(visible)
+    /**
+     * How many asteroids `coord` can see: one per direction with any asteroids in it.
+     */
+    pub fn visible_count_from(&self, coord: &Coordinate) -> usize {
+        self.in_sight(coord)
+            .map(|(towards, _)| towards)
+            .collect::<HashSet<_>>()
+            .len()
     }
 
+    /**
+     * The asteroid that sees the most others, and how many it sees. Ties go to the
+     * first in reading order.
+     */
     pub fn max_visible_from_count(&self) -> (Option<&Coordinate>, usize) {
-        if self.is_empty() {
-            return (None, 0);
-        }
+        self.coordinates
+            .par_iter()
+            .map(|coord| (self.visible_count_from(coord), coord))
+            .max_by(|(a, a_coord), (b, b_coord)| {
+                a.cmp(b).then_with(|| {
+                    ReadingOrder((*b_coord).clone())
+                        .cmp(&ReadingOrder((*a_coord).clone()))
+                })
+            })
+            .map_or((None, 0), |(count, coord)| (Some(coord), count))
+    }
 
-        let mut max_coord = None;
-        let mut max = 0;
+    // The other asteroids no obstacle hides from `from`, each with its direction.
+    fn in_sight<'a>(
+        &'a self,
+        from: &'a Coordinate,
+    ) -> impl Iterator<Item = (Coordinate, &'a Coordinate)> + 'a {
+        let mut blocked: HashMap<Coordinate, usize> = HashMap::new();
 
-        for coord in &self.coordinates {
-            // println!("coord: {:?}", coord);
-            let can_see_for_coord = self.visible_from(coord).unwrap().len();
+        for obstacle in &self.obstacles {
+            let distance = (obstacle - from).manhattan_distance();
+            let nearest = blocked.entry(direction(from, obstacle)).or_insert(distance);
 
-            if can_see_for_coord > max {
-                max_coord = Some(coord);
-                max = can_see_for_coord;
-            }
+            *nearest = distance.min(*nearest);
         }
 
-        (max_coord, max)
+        self.coordinates
+            .iter()
+            .filter(move |c| *c != from)
+            .map(move |c| (direction(from, c), c))
+            .filter(move |(towards, c)| {
+                blocked
+                    .get(towards)
+                    .is_none_or(|distance| (*c - from).manhattan_distance() < *distance)
+            })
     }
 }
 
-impl From<&str> for Region {
-    fn from(lines: &str) -> Self {
-        let mut coordinates: HashSet<Coordinate> = HashSet::new();
-
-        let rows = lines.trim().split_whitespace().collect::<Vec<_>>();
-        let height = rows.len();
-        let width = rows[0].len();
-
-        for (y, line) in rows.iter().enumerate() {
-            for (x, ch) in line.chars().enumerate() {
-                if Self::is_asteroid(ch) {
-                    let coordinate = Coordinate {
-                        x: x.try_into().unwrap(),
-                        y: y.try_into().unwrap(),
-                    };
-                    coordinates.insert(coordinate);
-                }
-            }
-        }
+// The smallest step from `from` that lands exactly on `to` when repeated.
+fn direction(from: &Coordinate, to: &Coordinate) -> Coordinate {
+    let diff = to - from;
+    let divisor = diff.x.gcd(&diff.y);
+
+    Coordinate::new(diff.x / divisor, diff.y / divisor)
+}
+
+impl From<&Grid2D<Cell>> for Region {
+    fn from(map: &Grid2D<Cell>) -> Self {
+        let all = |kinds: &[Cell]| {
+            map.iter()
+                .filter(|(_, cell)| kinds.contains(cell))
+                .map(|(coordinate, _)| coordinate)
+                .collect::<HashSet<_>>()
+        };
 
         Region {
-            width,
-            height,
-            coordinates,
+            width: map.width(),
+            height: map.height(),
+            coordinates: all(&[Cell::Asteroid, Cell::Station]),
+            station: all(&[Cell::Station]).into_iter().next(),
+            obstacles: all(&[Cell::Obstacle]),
+            cells: CellMap::default(),
         }
     }
 }
 
+/**
+ * Anything but `#` is empty space, short rows are padded with it and an empty map is
+ * an empty region. Use `Region::parse` to be told about mistakes.
+ */
+impl From<&str> for Region {
+    fn from(lines: &str) -> Self {
+        let rows = lines.split_whitespace().collect::<Vec<_>>();
+        let width = rows
+            .iter()
+            .map(|row| row.chars().count())
+            .max()
+            .unwrap_or(0);
+
+        let cells = rows
+            .iter()
+            .flat_map(|row| {
+                row.chars()
+                    .map(|ch| {
+                        if ch == '#' {
+                            Cell::Asteroid
+                        } else {
+                            Cell::Empty
+                        }
+                    })
+                    .chain(std::iter::repeat(Cell::Empty))
+                    .take(width)
+            })
+            .collect();
+
+        Self::from(&Grid2D::from_cells(width, rows.len(), cells))
+    }
+}
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    type Input = Region;
+
+    fn parse(input: &str) -> Result<Self::Input, Box<dyn Error>> {
+        Ok(Region::parse(input)?)
+    }
+
+    fn part1(region: &Self::Input) -> Answer {
+        Ok(region.max_visible_from_count().1.to_string())
+    }
+
+    fn part2(region: &Self::Input) -> Answer {
+        let station = region
+            .station
+            .as_ref()
+            .or_else(|| region.max_visible_from_count().0)
+            .ok_or("No asteroids to build a station on")?;
+
+        Obliteration::new(region, station)?
+            .nth(199)
+            .map(|c| (c.x * 100 + c.y).to_string())
+            .ok_or_else(|| "Fewer than 200 asteroids to vaporize".into())
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::grid2d::ParseGridErrorKind;
 
     #[test]
     fn test_slope() {
@@ -411,6 +604,99 @@ mod test {
         assert_eq!(region.contains(&Coordinate { x: 0, y: 0 }), false);
     }
 
+    #[test]
+    fn test_region_from_is_lenient() {
+        let region = Region::from("#.#\n##");
+
+        assert_eq!((region.width, region.height), (3, 2));
+        assert_eq!(region.len(), 4);
+        assert!(!region.contains(&Coordinate::new(2, 1)));
+
+        for text in &["", " \n\t\n"] {
+            let region = Region::from(*text);
+
+            assert!(region.is_empty());
+            assert_eq!((region.width, region.height), (0, 0));
+        }
+    }
+
+    #[test]
+    fn test_region_parse() {
+        let region = Region::parse("#.X\n.@#\n#..").unwrap();
+
+        assert_eq!(region.len(), 4);
+        assert_eq!(region.station, Some(Coordinate::new(2, 0)));
+        assert!(region.contains(&Coordinate::new(2, 0)));
+        assert_eq!(
+            region.obstacles,
+            vec![Coordinate::new(1, 1)].into_iter().collect()
+        );
+
+        let cells = CellMap {
+            empty: '-',
+            asteroid: '*',
+            station: 'S',
+            obstacle: 'O',
+        };
+        let region = Region::parse_with("*-S\n-O*", &cells).unwrap();
+
+        assert_eq!(region.len(), 3);
+        assert_eq!(region.station, Some(Coordinate::new(2, 0)));
+        assert_eq!(region.obstacles.len(), 1);
+    }
+
+    #[test]
+    fn test_region_parse_trailing_whitespace() {
+        let region = Region::parse("#.#  \n#.#\t\n").unwrap();
+
+        assert_eq!((region.width, region.height), (3, 2));
+        assert_eq!(region.len(), 4);
+
+        let cells = CellMap {
+            empty: ' ',
+            ..CellMap::default()
+        };
+        let region = Region::parse_with("# \n #", &cells).unwrap();
+
+        assert_eq!((region.width, region.height), (2, 2));
+        assert!(Region::parse_with("#  \n #", &cells).is_err());
+    }
+
+    #[test]
+    fn test_region_parse_errors() {
+        let error = |text| Region::parse(text).unwrap_err();
+
+        assert_eq!(
+            error("#.#\n##"),
+            ParseGridError {
+                row: 2,
+                column: 3,
+                kind: ParseGridErrorKind::RaggedRow {
+                    expected: 3,
+                    found: 2
+                }
+            }
+        );
+        assert_eq!(
+            error(".#.\n#.a").to_string(),
+            "row 2, column 3: unexpected 'a'"
+        );
+        assert_eq!(
+            error("X.#\n#.X").to_string(),
+            "row 2, column 3: unexpected 'X'"
+        );
+        assert_eq!(error("\n  \n").kind, ParseGridErrorKind::Empty);
+        assert_eq!(
+            error("\n\n#.#\n#a#\n").to_string(),
+            "row 4, column 2: unexpected 'a'"
+        );
+        assert_eq!(
+            error("  #X#\n  #.X\n").to_string(),
+            "row 2, column 5: unexpected 'X'"
+        );
+        assert!(Day10::parse("#.#\n#").is_err());
+    }
+
     #[test]
     fn test_derive_full_path() {
         let map = "
@@ -676,6 +962,74 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_visible_count_matches_paths() {
+        let region = Region::from(
+            "
+            .#..##.###...#######
+            ##.############..##.
+            .#.######.########.#
+            .###.#######.####.#.
+            #####.##.#.##.###.##
+            ",
+        );
+
+        for coord in &region.coordinates {
+            let by_path = region
+                .coordinates
+                .iter()
+                .filter(|c| *c != coord && region.can_see(coord, c).unwrap())
+                .count();
+
+            assert_eq!(region.visible_count_from(coord), by_path, "{:?}", coord);
+            assert_eq!(region.visible_from(coord).unwrap().len(), by_path);
+        }
+    }
+
+    #[test]
+    fn test_obstacles() {
+        let region = Region::parse("#.@.#.#\n#......").unwrap();
+        let (left, middle) = (Coordinate::new(0, 0), Coordinate::new(4, 0));
+
+        assert_eq!(region.can_see(&left, &middle), Ok(false));
+        assert_eq!(region.visible_count_from(&left), 1);
+        assert_eq!(region.visible_count_from(&middle), 2);
+
+        for coord in &region.coordinates {
+            let by_path = region
+                .coordinates
+                .iter()
+                .filter(|c| *c != coord && region.can_see(coord, c).unwrap())
+                .count();
+
+            assert_eq!(region.visible_count_from(coord), by_path, "{:?}", coord);
+            assert_eq!(region.visible_from(coord).unwrap().len(), by_path);
+        }
+
+        // Nothing behind the obstacle is ever hit.
+        let ob = Obliteration::new(&region, &middle).unwrap();
+        assert_eq!(
+            ob.collect::<Vec<_>>(),
+            vec![Coordinate::new(6, 0), Coordinate::new(0, 1)]
+        );
+    }
+
+    #[test]
+    fn test_max_visible_tie_break() {
+        // Every asteroid sees the other two.
+        let region = Region::from("#.#\n...\n.#.");
+
+        assert_eq!(
+            region.max_visible_from_count(),
+            (Some(&Coordinate { x: 0, y: 0 }), 2)
+        );
+        assert_eq!(
+            Region::from("...\n.#.").max_visible_from_count(),
+            (Some(&Coordinate { x: 1, y: 1 }), 0)
+        );
+        assert_eq!(Region::from("...").max_visible_from_count(), (None, 0));
+    }
+
     #[test]
     fn test_max_visible_from_count_bigger_1() {
         let map = "
@@ -810,6 +1164,96 @@ mod test {
         assert_eq!(ob.nth(199), Some(Coordinate { x: 8, y: 2 }));
     }
 
+    #[test]
+    fn test_obliteration_order() {
+        let map = "
+        .#....#####...#..
+        ##...##.#####..##
+        ##...#...#.#####.
+        ..#.....#...###..
+        ..#.#.....#....##
+        ";
+
+        let region = Region::from(map);
+        let ob = Obliteration::new(&region, &Coordinate::new(8, 3)).unwrap();
+
+        assert_eq!(ob.len(), region.len() - 1);
+        assert_eq!(
+            ob.order()[..9],
+            [
+                Coordinate::new(8, 1),
+                Coordinate::new(9, 0),
+                Coordinate::new(9, 1),
+                Coordinate::new(10, 0),
+                Coordinate::new(9, 2),
+                Coordinate::new(11, 1),
+                Coordinate::new(12, 1),
+                Coordinate::new(11, 2),
+                Coordinate::new(15, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_obliteration_rotation() {
+        let map = "
+        ..#..
+        ..#..
+        #.#.#
+        ..#..
+        ";
+
+        let region = Region::from(map);
+        let station = Coordinate::new(2, 2);
+        let order = |start: (isize, isize), rotation| {
+            Obliteration::with_rotation(&region, &station, &start.into(), rotation)
+                .unwrap()
+                .collect::<Vec<_>>()
+        };
+        let coordinates = |cs: &[(isize, isize)]| {
+            cs.iter().map(|&c| Coordinate::from(c)).collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            order((0, -1), Rotation::Clockwise),
+            coordinates(&[(2, 1), (4, 2), (2, 3), (0, 2), (2, 0)])
+        );
+        assert_eq!(
+            order((0, -1), Rotation::CounterClockwise),
+            coordinates(&[(2, 1), (0, 2), (2, 3), (4, 2), (2, 0)])
+        );
+        assert_eq!(
+            order((1, 0), Rotation::Clockwise),
+            coordinates(&[(4, 2), (2, 3), (0, 2), (2, 1), (2, 0)])
+        );
+        // Starting between directions goes to the next one along.
+        assert_eq!(
+            order((1, -1), Rotation::CounterClockwise),
+            coordinates(&[(2, 1), (0, 2), (2, 3), (4, 2), (2, 0)])
+        );
+
+        assert!(Obliteration::with_rotation(
+            &region,
+            &station,
+            &Coordinate::default(),
+            Rotation::Clockwise
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_obliteration_nth() {
+        let region = Region::from("#.#\n.#.\n#.#");
+        let mut ob = Obliteration::new(&region, &Coordinate::new(1, 1)).unwrap();
+
+        assert_eq!(ob.len(), 4);
+        assert_eq!(ob.nth(1), Some(Coordinate::new(2, 2)));
+        assert_eq!(ob.len(), 2);
+        assert_eq!(ob.nth(1), Some(Coordinate::new(0, 0)));
+        assert_eq!(ob.next(), None);
+        assert_eq!(ob.nth(usize::MAX), None);
+    }
+
     #[test]
     #[ignore]
     fn test_obliteration_order_biggest_count() {
@@ -0,0 +1,393 @@
+use adventofcode2019rust::answers::{Answers, Report, Status, Verification};
+use adventofcode2019rust::input::{self, Inputs};
+use adventofcode2019rust::solution::{self, Part};
+use std::env;
+use std::error::Error;
+use std::path::PathBuf;
+use std::process;
+use std::time::{Duration, Instant};
+
+const USAGE: &str = "Usage: aoc <day|all> [options]
+
+Solves a day's puzzle and prints each answer with how long it took.
+
+Options:
+    --part N        Only run part N (1 or 2)
+    --input PATH    Read the puzzle input from PATH
+    --input-dir DIR Look for dNN.txt in DIR before the bundled inputs
+                    (defaults to $AOC_INPUT_DIR)
+    --verify        Check each answer against the expected answers
+    --answers PATH  Read expected answers from PATH (defaults to answers.txt in
+                    the input directory, or the answers for the bundled inputs)
+    -h, --help      Show this message";
+
+#[derive(Debug, Default, PartialEq)]
+struct Options {
+    days: Vec<u32>,
+    part: Option<Part>,
+    input: Option<PathBuf>,
+    input_dir: Option<PathBuf>,
+    verify: bool,
+    answers: Option<PathBuf>,
+}
+
+impl Options {
+    fn parse(args: impl Iterator<Item = String>) -> Result<Options, String> {
+        let mut options = Options::default();
+        let mut days = None;
+        let mut args = args;
+
+        while let Some(arg) = args.next() {
+            let mut value =
+                |name: &str| args.next().ok_or_else(|| format!("{} needs a value", name));
+
+            match arg.as_str() {
+                "--part" => {
+                    options.part = match value("--part")?.as_str() {
+                        "1" => Some(Part::One),
+                        "2" => Some(Part::Two),
+                        part => return Err(format!("Invalid part: {}", part)),
+                    }
+                }
+                "--input" => options.input = Some(PathBuf::from(value("--input")?)),
+                "--input-dir" => {
+                    options.input_dir = Some(PathBuf::from(value("--input-dir")?))
+                }
+                "--verify" => options.verify = true,
+                "--answers" => options.answers = Some(PathBuf::from(value("--answers")?)),
+                "-h" | "--help" => return Err(USAGE.to_string()),
+                a if a.starts_with("--") => return Err(format!("Unknown option: {}", a)),
+                "all" if days.is_none() => days = Some(solution::days()),
+                _ if days.is_none() => {
+                    let day = arg.parse().map_err(|_| format!("Invalid day: {}", arg))?;
+                    days = Some(vec![day]);
+                }
+                _ => return Err(format!("Unexpected argument: {}", arg)),
+            }
+        }
+
+        options.days = days.ok_or_else(|| USAGE.to_string())?;
+
+        if options.input.is_some() && options.days.len() > 1 {
+            return Err("--input only works for a single day".to_string());
+        }
+
+        Ok(options)
+    }
+
+    fn inputs(&self) -> Inputs {
+        match &self.input_dir {
+            Some(dir) => Inputs::new(Some(dir.clone())),
+            None => Inputs::from_env(),
+        }
+    }
+
+    fn load_input(
+        &self,
+        inputs: &mut Inputs,
+        day: u32,
+    ) -> Result<String, input::InputError> {
+        match &self.input {
+            Some(path) => input::read_file(path),
+            None => inputs.load(day).map(str::to_string),
+        }
+    }
+
+    // The bundled answers only apply to the bundled inputs, never to a single
+    // --input file or to anything found in the input directory.
+    fn answers(&self, inputs: &Inputs) -> Result<Answers, Box<dyn Error>> {
+        if let Some(path) = &self.answers {
+            return Answers::load(path);
+        }
+
+        if self.input.is_some() {
+            return Ok(Answers::default());
+        }
+
+        match inputs.dir().map(|dir| dir.join("answers.txt")) {
+            Some(path) if path.exists() => Answers::load(&path),
+            _ => {
+                let mut answers = Answers::bundled();
+                answers.retain(|day, _| inputs.is_bundled(day));
+                Ok(answers)
+            }
+        }
+    }
+}
+
+fn format_duration(duration: Duration) -> String {
+    let micros = duration.as_micros();
+
+    match micros {
+        0..=999 => format!("{}µs", micros),
+        1_000..=999_999 => format!("{:.1}ms", micros as f64 / 1_000.0),
+        _ => format!("{:.2}s", micros as f64 / 1_000_000.0),
+    }
+}
+
+fn format_status(status: &Status) -> &'static str {
+    match status {
+        Status::Correct => " [ok]",
+        Status::Mismatch { .. } => " [WRONG]",
+        Status::Missing => " [no expected answer]",
+        Status::Failed(_) => " [FAILED]",
+    }
+}
+
+// Multi-line answers (like rendered images) start on their own line.
+fn format_answer(
+    day: u32,
+    part: Part,
+    answer: &str,
+    duration: Duration,
+    status: Option<&Status>,
+) -> String {
+    let separator = if answer.contains('\n') { "\n" } else { " " };
+
+    let mut text = format!(
+        "Day {} part {} ({}){}:{}{}",
+        day,
+        part.number(),
+        format_duration(duration),
+        status.map_or("", format_status),
+        separator,
+        answer.trim_end_matches('\n')
+    );
+
+    if let Some(Status::Mismatch { expected }) = status {
+        let separator = if expected.contains('\n') { "\n" } else { " " };
+        text.push_str(&format!("\n  expected:{}{}", separator, expected));
+    }
+
+    text
+}
+
+fn format_list(results: &[&Verification]) -> String {
+    results
+        .iter()
+        .map(|v| format!("day {} part {}", v.day, v.part.number()))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn main() {
+    let options = match Options::parse(env::args().skip(1)) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{}", message);
+            process::exit(2);
+        }
+    };
+
+    if let Some(day) = options
+        .days
+        .iter()
+        .find(|day| solution::runner(**day).is_none())
+    {
+        eprintln!("Day {} is not solved yet", day);
+        process::exit(2);
+    }
+
+    let mut inputs = options.inputs();
+
+    let answers = if options.verify {
+        match options.answers(&inputs) {
+            Ok(answers) => Some(answers),
+            Err(e) => {
+                eprintln!("{}", e);
+                process::exit(1);
+            }
+        }
+    } else {
+        None
+    };
+
+    let parts = match options.part {
+        Some(part) => vec![part],
+        None => vec![Part::One, Part::Two],
+    };
+
+    let mut report = Report::default();
+
+    for &day in &options.days {
+        let run = solution::runner(day).unwrap();
+
+        let input = match options.load_input(&mut inputs, day) {
+            Ok(input) => input,
+            Err(e) => {
+                eprintln!("{}", e);
+                process::exit(1);
+            }
+        };
+
+        for &part in &parts {
+            let start = Instant::now();
+            let answer = run(&input, part);
+            let duration = start.elapsed();
+
+            let status = answers
+                .as_ref()
+                .map(|answers| answers.check(day, part, &answer));
+
+            match &answer {
+                Ok(actual) => {
+                    println!(
+                        "{}",
+                        format_answer(day, part, actual, duration, status.as_ref())
+                    )
+                }
+                Err(e) if status.is_some() => {
+                    eprintln!("Day {} part {} failed: {}", day, part.number(), e)
+                }
+                Err(e) => {
+                    eprintln!("Day {} failed: {}", day, e);
+                    process::exit(1);
+                }
+            }
+
+            if let Some(status) = status {
+                report.results.push(Verification {
+                    day,
+                    part,
+                    actual: answer.ok(),
+                    status,
+                });
+            }
+        }
+    }
+
+    if !options.verify {
+        return;
+    }
+
+    println!("\n{}", report);
+
+    for (label, results) in &[
+        ("Wrong", report.mismatches()),
+        ("Failed", report.failures()),
+        ("No expected answer", report.missing()),
+    ] {
+        if !results.is_empty() {
+            println!("{}: {}", label, format_list(results));
+        }
+    }
+
+    if !report.is_ok() {
+        process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Options, String> {
+        Options::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn test_parse_options() {
+        assert_eq!(
+            parse(&["3", "--part", "2", "--input", "example.txt"]),
+            Ok(Options {
+                days: vec![3],
+                part: Some(Part::Two),
+                input: Some(PathBuf::from("example.txt")),
+                ..Default::default()
+            })
+        );
+
+        assert_eq!(
+            parse(&["all", "--verify", "--answers", "mine.txt"]),
+            Ok(Options {
+                days: solution::days(),
+                verify: true,
+                answers: Some(PathBuf::from("mine.txt")),
+                ..Default::default()
+            })
+        );
+
+        assert_eq!(
+            parse(&["5", "--input-dir", "inputs"])
+                .unwrap()
+                .inputs()
+                .dir(),
+            Some(PathBuf::from("inputs").as_path())
+        );
+    }
+
+    #[test]
+    fn test_parse_options_errors() {
+        assert_eq!(parse(&[]), Err(USAGE.to_string()));
+        assert!(parse(&["x"]).is_err());
+        assert!(parse(&["1", "--part", "3"]).is_err());
+        assert!(parse(&["1", "--input"]).is_err());
+        assert!(parse(&["1", "--input-dir"]).is_err());
+        assert!(parse(&["1", "--answers"]).is_err());
+        assert!(parse(&["1", "2"]).is_err());
+        assert!(parse(&["all", "--input", "example.txt"]).is_err());
+    }
+
+    #[test]
+    fn test_answers() {
+        let options = parse(&["1", "--verify"]).unwrap();
+        let answers = options.answers(&Inputs::new(None)).unwrap();
+        assert_eq!(answers.get(1, Part::One), Some("3318195"));
+
+        let options = parse(&["1", "--verify", "--input", "example.txt"]).unwrap();
+        assert!(options.answers(&Inputs::new(None)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_answers_for_own_inputs() {
+        let dir = env::temp_dir().join(format!("aoc-answers-{}", process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("d01.txt"), "12\n14\n").unwrap();
+
+        let options =
+            parse(&["1", "--verify", "--input-dir", dir.to_str().unwrap()]).unwrap();
+        let answers = options.answers(&options.inputs()).unwrap();
+
+        assert_eq!(answers.get(1, Part::One), None);
+        assert_eq!(
+            answers.check(1, Part::One, &Ok("4".to_string())),
+            Status::Missing
+        );
+        assert_eq!(
+            answers.get(2, Part::One),
+            Answers::bundled().get(2, Part::One)
+        );
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_format_answer() {
+        let duration = Duration::from_micros(1_500);
+
+        assert_eq!(
+            format_answer(1, Part::One, "3318195", duration, None),
+            "Day 1 part 1 (1.5ms): 3318195"
+        );
+        assert_eq!(
+            format_answer(8, Part::Two, "# \n #\n", duration, None),
+            "Day 8 part 2 (1.5ms):\n# \n #"
+        );
+        assert_eq!(
+            format_answer(1, Part::One, "3318195", duration, Some(&Status::Correct)),
+            "Day 1 part 1 (1.5ms) [ok]: 3318195"
+        );
+        assert_eq!(
+            format_answer(
+                1,
+                Part::Two,
+                "12",
+                duration,
+                Some(&Status::Mismatch {
+                    expected: "13".to_string()
+                })
+            ),
+            "Day 1 part 2 (1.5ms) [WRONG]: 12\n  expected: 13"
+        );
+    }
+}
@@ -0,0 +1,209 @@
+use adventofcode2019rust::{code, fuel, input, program, reference};
+use std::env;
+use std::fmt::Debug;
+use std::process;
+use std::time::{Duration, Instant};
+
+const USAGE: &str = "Usage: compare [--runs N]
+
+Times the alternative implementations shipped side by side and reports which is
+faster. Build with --release for meaningful numbers.
+
+Options:
+    --runs N        Time each variant N times and report the median (default 5)
+    -h, --help      Show this message";
+
+fn parse_runs(args: impl Iterator<Item = String>) -> Result<usize, String> {
+    let mut runs = 5;
+    let mut args = args;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--runs" => {
+                let value = args.next().ok_or("--runs needs a value")?;
+                runs = match value.parse() {
+                    Ok(n) if n > 0 => n,
+                    _ => return Err(format!("Invalid number of runs: {}", value)),
+                };
+            }
+            "-h" | "--help" => return Err(USAGE.to_string()),
+            a => return Err(format!("Unknown argument: {}", a)),
+        }
+    }
+
+    Ok(runs)
+}
+
+fn median_time<T>(runs: usize, f: &dyn Fn() -> T) -> (Duration, T) {
+    let mut times = Vec::with_capacity(runs);
+    let mut result = None;
+
+    for _ in 0..runs {
+        let start = Instant::now();
+        let value = f();
+        times.push(start.elapsed());
+        result = Some(value);
+    }
+
+    times.sort();
+
+    (times[runs / 2], result.unwrap())
+}
+
+fn format_duration(duration: Duration) -> String {
+    format!("{:.3}ms", duration.as_secs_f64() * 1_000.0)
+}
+
+// Times two variants, checks that they agree, and returns the report lines.
+fn compare<T: PartialEq + Debug>(
+    title: &str,
+    runs: usize,
+    a: (&str, &dyn Fn() -> T),
+    b: (&str, &dyn Fn() -> T),
+) -> Vec<String> {
+    let (a_time, a_result) = median_time(runs, a.1);
+    let (b_time, b_result) = median_time(runs, b.1);
+
+    let (faster, ratio) = if a_time <= b_time {
+        (a.0, b_time.as_secs_f64() / a_time.as_secs_f64().max(1e-9))
+    } else {
+        (b.0, a_time.as_secs_f64() / b_time.as_secs_f64().max(1e-9))
+    };
+
+    let mut lines = vec![
+        title.to_string(),
+        format!("  {:<32} {:>12}", a.0, format_duration(a_time)),
+        format!("  {:<32} {:>12}", b.0, format_duration(b_time)),
+        format!("  {} is {:.2}x faster", faster, ratio),
+    ];
+
+    if a_result != b_result {
+        lines.push(format!(
+            "  RESULTS DIFFER: {:?} vs {:?}",
+            a_result, b_result
+        ));
+    }
+
+    lines
+}
+
+fn main() {
+    let runs = match parse_runs(env::args().skip(1)) {
+        Ok(runs) => runs,
+        Err(message) => {
+            eprintln!("{}", message);
+            process::exit(2);
+        }
+    };
+
+    let range = 273_025..=767_253;
+    let sample = (273_025..=767_253).step_by(97).collect::<Vec<u32>>();
+    let d09 = program::parse_program(input::bundled(9).unwrap()).unwrap();
+
+    let masses = (0..1_000_000)
+        .map(|n| 1_000 + n * 7_919)
+        .collect::<Vec<i64>>();
+
+    let reports = vec![
+        compare(
+            &format!("Total fuel ({} masses)", masses.len()),
+            runs,
+            ("total_fuel_needed_for_mass", &|| {
+                masses
+                    .iter()
+                    .map(|mass| fuel::total_fuel_needed_for_mass(*mass))
+                    .sum::<i64>()
+            }),
+            ("FuelTable::total_fuel_for_par", &|| {
+                fuel::FuelTable::new(fuel::DEFAULT_TABLE_SIZE).total_fuel_for_par(&masses)
+            }),
+        ),
+        compare(
+            "Day 4 part 1 (273025-767253)",
+            runs,
+            ("find_candidates", &|| {
+                code::find_candidates(range.clone()).len()
+            }),
+            ("find_candidates2", &|| {
+                code::find_candidates2(range.clone()).len()
+            }),
+        ),
+        compare(
+            "Day 4 part 2 (273025-767253)",
+            runs,
+            ("find_candidates_with_one_dup", &|| {
+                code::find_candidates_with_one_dup(range.clone()).len()
+            }),
+            ("find_candidates_with_one_dup2", &|| {
+                code::find_candidates_with_one_dup2(range.clone()).len()
+            }),
+        ),
+        compare(
+            &format!("Single number checks ({} numbers)", sample.len()),
+            runs,
+            ("is_candidate", &|| {
+                sample.iter().filter(|n| code::is_candidate(**n)).count()
+            }),
+            ("is_candidate2", &|| {
+                sample.iter().filter(|n| code::is_candidate2(**n)).count()
+            }),
+        ),
+        compare(
+            "Day 9 part 2 Intcode",
+            runs,
+            ("program::Program", &|| {
+                program::run_program_with_input(&d09, 2)
+                    .all_output()
+                    .to_vec()
+            }),
+            ("reference::Machine", &|| {
+                reference::run_reference(&d09, &[2], usize::MAX).0.output
+            }),
+        ),
+    ];
+
+    for report in reports {
+        println!("{}\n", report.join("\n"));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<usize, String> {
+        parse_runs(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn test_parse_runs() {
+        assert_eq!(parse(&[]), Ok(5));
+        assert_eq!(parse(&["--runs", "3"]), Ok(3));
+        assert!(parse(&["--runs", "0"]).is_err());
+        assert!(parse(&["--runs"]).is_err());
+        assert!(parse(&["--bogus"]).is_err());
+    }
+
+    #[test]
+    fn test_compare() {
+        let report = compare(
+            "Sum",
+            3,
+            ("fold", &|| (1..=10).sum::<i32>()),
+            ("formula", &|| 55),
+        );
+
+        assert_eq!(report[0], "Sum");
+        assert_eq!(report.len(), 4);
+
+        let report = compare(
+            "Sum",
+            1,
+            ("fold", &|| (1..=10).sum::<i32>()),
+            ("wrong", &|| 54),
+        );
+
+        assert_eq!(report.len(), 5);
+        assert!(report[4].contains("RESULTS DIFFER"));
+    }
+}
@@ -0,0 +1,303 @@
+use adventofcode2019rust::program::{self, Program};
+use std::env;
+use std::fs;
+use std::io::{self, Read};
+use std::process;
+
+const USAGE: &str = "Usage: intcode <program-file> [options] [input...]
+
+Runs an Intcode program and prints its output.
+
+Options:
+    --stdin         Read inputs from stdin as well as from the arguments
+    --ascii         Print output as ASCII text, and read stdin as text
+    --set POS=VAL   Write VAL to memory at POS before running (repeatable)
+    --dump          Print memory once the program stops
+    --trace         Print each instruction to stderr before it runs
+    --limit N       Stop after running N instructions
+    -h, --help      Show this message";
+
+#[derive(Debug, Default, PartialEq)]
+struct Options {
+    path: String,
+    inputs: Vec<i64>,
+    stdin: bool,
+    ascii: bool,
+    patches: Vec<(usize, i64)>,
+    dump: bool,
+    trace: bool,
+    limit: Option<usize>,
+    help: bool,
+}
+
+impl Options {
+    fn parse(args: impl Iterator<Item = String>) -> Result<Options, String> {
+        let mut options = Options::default();
+        let mut path = None;
+        let mut args = args;
+
+        while let Some(arg) = args.next() {
+            let mut value =
+                |name: &str| args.next().ok_or_else(|| format!("{} needs a value", name));
+
+            match arg.as_str() {
+                "--stdin" => options.stdin = true,
+                "--ascii" => options.ascii = true,
+                "--dump" => options.dump = true,
+                "--trace" => options.trace = true,
+                "--set" => options.patches.push(parse_patch(&value("--set")?)?),
+                "--limit" => {
+                    let limit = value("--limit")?;
+                    options.limit = Some(
+                        limit
+                            .parse()
+                            .map_err(|_| format!("Invalid limit: {}", limit))?,
+                    );
+                }
+                "-h" | "--help" => {
+                    options.help = true;
+                    return Ok(options);
+                }
+                a if a.starts_with("--") => return Err(format!("Unknown option: {}", a)),
+                _ if path.is_none() => path = Some(arg),
+                _ => options.inputs.extend(parse_inputs(&arg)?),
+            }
+        }
+
+        options.path = path.ok_or_else(|| USAGE.to_string())?;
+
+        Ok(options)
+    }
+}
+
+fn parse_patch(text: &str) -> Result<(usize, i64), String> {
+    let invalid = || format!("Invalid --set {}, expected POS=VAL", text);
+
+    let mut parts = text.splitn(2, '=');
+    let pos = parts.next().and_then(|pos| pos.trim().parse().ok());
+    let value = parts.next().and_then(|value| value.trim().parse().ok());
+
+    match (pos, value) {
+        (Some(pos), Some(value)) => Ok((pos, value)),
+        _ => Err(invalid()),
+    }
+}
+
+// Inputs are numbers separated by commas or whitespace.
+fn parse_inputs(text: &str) -> Result<Vec<i64>, String> {
+    text.split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|input| !input.is_empty())
+        .map(|input| {
+            input
+                .parse()
+                .map_err(|_| format!("Invalid input: {}", input))
+        })
+        .collect()
+}
+
+// Names the word that isn't a number, counting from 1.
+fn parse_code(path: &str, text: &str) -> Result<Vec<i64>, String> {
+    program::parse_program(text).map_err(|e| {
+        let (position, word) = text
+            .trim()
+            .split(',')
+            .map(str::trim)
+            .enumerate()
+            .find(|(_, word)| word.parse::<i64>().is_err())
+            .unwrap_or((0, ""));
+
+        format!(
+            "Could not parse {}: word {} ({:?}): {}",
+            path,
+            position + 1,
+            word,
+            e
+        )
+    })
+}
+
+fn format_output(output: &[i64], ascii: bool) -> String {
+    if !ascii {
+        return output.iter().map(|value| format!("{}\n", value)).collect();
+    }
+
+    // Anything outside ASCII (usually the answer) is printed as a number.
+    output
+        .iter()
+        .map(|value| match *value {
+            0..=127 => (*value as u8 as char).to_string(),
+            _ => format!("{}\n", value),
+        })
+        .collect()
+}
+
+fn run(options: &Options) -> Result<Program, String> {
+    let text = fs::read_to_string(&options.path)
+        .map_err(|e| format!("Could not read {}: {}", options.path, e))?;
+    let mut code = parse_code(&options.path, &text)?;
+
+    for (pos, value) in &options.patches {
+        if *pos >= code.len() {
+            code.resize(pos + 1, 0);
+        }
+        code[*pos] = *value;
+    }
+
+    let mut inputs = options.inputs.clone();
+
+    if options.stdin {
+        let mut text = String::new();
+        io::stdin()
+            .read_to_string(&mut text)
+            .map_err(|e| format!("Could not read stdin: {}", e))?;
+
+        if options.ascii {
+            inputs.extend(text.bytes().map(i64::from));
+        } else {
+            inputs.extend(parse_inputs(&text)?);
+        }
+    }
+
+    if !options.trace && options.limit.is_none() {
+        return Ok(program::run_program_with_inputs(&code, &inputs));
+    }
+
+    let mut program = Program::new(&code, &inputs);
+    let mut steps = 0;
+
+    loop {
+        if options.limit == Some(steps) {
+            eprintln!("Stopped after {} instructions", steps);
+            break;
+        }
+
+        if options.trace {
+            eprintln!(
+                "{:>6} rb={:<6} {:?}",
+                program.position(),
+                program.relative_base(),
+                program.next_instruction()
+            );
+        }
+
+        if !program.step() {
+            break;
+        }
+
+        steps += 1;
+    }
+
+    Ok(program)
+}
+
+fn main() {
+    let options = match Options::parse(env::args().skip(1)) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{}", message);
+            process::exit(2);
+        }
+    };
+
+    if options.help {
+        println!("{}", USAGE);
+        return;
+    }
+
+    let program = match run(&options) {
+        Ok(program) => program,
+        Err(message) => {
+            eprintln!("{}", message);
+            process::exit(1);
+        }
+    };
+
+    print!("{}", format_output(program.all_output(), options.ascii));
+
+    if options.dump {
+        let memory = program
+            .code
+            .iter()
+            .map(|value| value.to_string())
+            .collect::<Vec<_>>();
+        println!("{}", memory.join(","));
+    }
+
+    if !program.is_finished() {
+        eprintln!(
+            "Program did not halt (stopped at position {})",
+            program.position()
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Options, String> {
+        Options::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn test_parse_options() {
+        assert_eq!(
+            parse(&["d02.txt", "--set", "1=12", "--set", "2=2", "--dump"]),
+            Ok(Options {
+                path: "d02.txt".to_string(),
+                patches: vec![(1, 12), (2, 2)],
+                dump: true,
+                ..Default::default()
+            })
+        );
+
+        assert_eq!(
+            parse(&["d07.txt", "4,0", "--trace", "--limit", "100", "7"]),
+            Ok(Options {
+                path: "d07.txt".to_string(),
+                inputs: vec![4, 0, 7],
+                trace: true,
+                limit: Some(100),
+                ..Default::default()
+            })
+        );
+    }
+
+    #[test]
+    fn test_help() {
+        for args in &[&["--help"][..], &["d02.txt", "-h", "--bogus"]] {
+            assert_eq!(parse(args).map(|options| options.help), Ok(true));
+        }
+    }
+
+    #[test]
+    fn test_parse_code() {
+        assert_eq!(
+            parse_code("p.txt", "1,0, 0,3,99\n"),
+            Ok(vec![1, 0, 0, 3, 99])
+        );
+        assert_eq!(
+            parse_code("p.txt", "1,0,x,99"),
+            Err(
+                "Could not parse p.txt: word 3 (\"x\"): invalid digit found in string"
+                    .to_string()
+            )
+        );
+        assert!(parse_code("p.txt", "").is_err());
+    }
+
+    #[test]
+    fn test_parse_options_errors() {
+        assert_eq!(parse(&[]), Err(USAGE.to_string()));
+        assert!(parse(&["d02.txt", "--set", "1:12"]).is_err());
+        assert!(parse(&["d02.txt", "--limit"]).is_err());
+        assert!(parse(&["d02.txt", "--bogus"]).is_err());
+        assert!(parse(&["d02.txt", "x"]).is_err());
+    }
+
+    #[test]
+    fn test_format_output() {
+        assert_eq!(format_output(&[1, -2], false), "1\n-2\n");
+        assert_eq!(format_output(&[72, 105, 10, 1000], true), "Hi\n1000\n");
+    }
+}
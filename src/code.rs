@@ -1,9 +1,12 @@
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::error::Error;
 use std::ops::RangeInclusive;
 
 use rayon::prelude::*;
 
+use crate::solution::{Answer, Solution};
+
 /**
  * This function is not efficient, but it is made better by using Rayon parallel filter.
  */
@@ -150,21 +153,19 @@ fn has_increasing_digits(number: u32) -> bool {
     })
 }
 
-fn is_candidate(n: u32) -> bool {
+pub fn is_candidate(n: u32) -> bool {
     has_increasing_digits(n) && has_adjacent_dup(n)
 }
 
-#[allow(dead_code)]
-fn is_candidate2(n: u32) -> bool {
+pub fn is_candidate2(n: u32) -> bool {
     find_candidates2(n..=n).len() == 1
 }
 
-fn is_candidate_with_one_dup(n: u32) -> bool {
+pub fn is_candidate_with_one_dup(n: u32) -> bool {
     has_increasing_digits(n) && has_one_adjacent_dup(n)
 }
 
-#[allow(dead_code)]
-fn is_candidate_with_one_dup2(n: u32) -> bool {
+pub fn is_candidate_with_one_dup2(n: u32) -> bool {
     find_candidates_with_one_dup2(n..=n).len() == 1
 }
 
@@ -280,6 +281,35 @@ impl DoubleEndedIterator for Digits {
     }
 }
 
+pub struct Day04;
+
+impl Solution for Day04 {
+    type Input = RangeInclusive<u32>;
+
+    fn parse(input: &str) -> Result<Self::Input, Box<dyn Error>> {
+        let mut bounds = input.trim().splitn(2, '-');
+
+        match (bounds.next(), bounds.next()) {
+            (Some(start), Some(end)) => Ok(start.parse()?..=end.parse()?),
+            _ => Err(format!(
+                "Expected a range like 100000-999999, got {}",
+                input.trim()
+            )
+            .into()),
+        }
+    }
+
+    fn part1(range: &Self::Input) -> Answer {
+        Ok(find_candidates2(range.clone()).len().to_string())
+    }
+
+    fn part2(range: &Self::Input) -> Answer {
+        Ok(find_candidates_with_one_dup2(range.clone())
+            .len()
+            .to_string())
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -1,3 +1,11 @@
+use crate::solution::{Answer, Solution};
+use num::{BigUint, Integer};
+use rayon::prelude::*;
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
 pub fn total_fuel_needed_for_mass(mass: i64) -> i64 {
     let mut total: i64 = 0;
     let mut calculated: i64;
@@ -22,9 +30,657 @@ pub fn fuel_needed_for_mass(mass: i64) -> i64 {
     mass / 3 - 2
 }
 
+#[derive(Debug, PartialEq)]
+pub enum FuelError {
+    NegativeMass(i64),
+    Overflow,
+}
+
+impl Error for FuelError {}
+
+impl fmt::Display for FuelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FuelError::NegativeMass(mass) => write!(f, "Mass {} is negative", mass),
+            FuelError::Overflow => write!(f, "Fuel total overflows"),
+        }
+    }
+}
+
+/**
+ * `fuel_needed_for_mass` for masses that make sense: negative masses are an error,
+ * and masses under 6 need no fuel rather than negative fuel.
+ */
+pub fn checked_fuel_needed_for_mass(mass: i64) -> Result<i64, FuelError> {
+    if mass < 0 {
+        return Err(FuelError::NegativeMass(mass));
+    }
+
+    Ok((mass / 3).checked_sub(2).ok_or(FuelError::Overflow)?.max(0))
+}
+
+pub fn checked_total_fuel_needed_for_mass(mass: i64) -> Result<i64, FuelError> {
+    let mut total: i64 = 0;
+    let mut fuel = checked_fuel_needed_for_mass(mass)?;
+
+    while fuel > 0 {
+        total = total.checked_add(fuel).ok_or(FuelError::Overflow)?;
+        fuel = checked_fuel_needed_for_mass(fuel)?;
+    }
+
+    Ok(total)
+}
+
+/**
+ * Sums fuel totals, or gives `None` rather than wrapping around.
+ */
+pub fn checked_sum(fuels: impl IntoIterator<Item = i64>) -> Option<i64> {
+    fuels
+        .into_iter()
+        .try_fold(0i64, |total, fuel| total.checked_add(fuel))
+}
+
+pub fn checked_total_fuel(
+    masses: impl IntoIterator<Item = i64>,
+) -> Result<i64, FuelError> {
+    masses.into_iter().try_fold(0i64, |total, mass| {
+        total
+            .checked_add(checked_total_fuel_needed_for_mass(mass)?)
+            .ok_or(FuelError::Overflow)
+    })
+}
+
+pub fn fuel_needed_for_mass_u128(mass: u128) -> u128 {
+    (mass / 3).saturating_sub(2)
+}
+
+// Fuel is under half the mass, so this can't overflow.
+pub fn total_fuel_needed_for_mass_u128(mass: u128) -> u128 {
+    let mut total = 0;
+    let mut fuel = fuel_needed_for_mass_u128(mass);
+
+    while fuel > 0 {
+        total += fuel;
+        fuel = fuel_needed_for_mass_u128(fuel);
+    }
+
+    total
+}
+
+/**
+ * Adds up total fuel in a `u128`, which has room for any number of `i64` masses
+ * this side of 2^64 modules.
+ */
+pub fn total_fuel_wide(masses: impl IntoIterator<Item = i64>) -> Result<u128, FuelError> {
+    masses.into_iter().try_fold(0u128, |total, mass| {
+        if mass < 0 {
+            return Err(FuelError::NegativeMass(mass));
+        }
+
+        total
+            .checked_add(total_fuel_needed_for_mass_u128(mass as u128))
+            .ok_or(FuelError::Overflow)
+    })
+}
+
+pub fn fuel_needed_for_mass_big(mass: &BigUint) -> BigUint {
+    let third = mass / 3u32;
+    let two = BigUint::from(2u32);
+
+    if third > two {
+        third - two
+    } else {
+        BigUint::from(0u32)
+    }
+}
+
+pub fn total_fuel_needed_for_mass_big(mass: &BigUint) -> BigUint {
+    let mut total = BigUint::from(0u32);
+    let mut fuel = fuel_needed_for_mass_big(mass);
+
+    while fuel > BigUint::from(0u32) {
+        total += &fuel;
+        fuel = fuel_needed_for_mass_big(&fuel);
+    }
+
+    total
+}
+
+/**
+ * Each fuel stage for a mass: the fuel for the mass itself, then the fuel for that
+ * fuel, and so on while it is still positive.
+ */
+pub fn fuel_stages_for_mass(mass: i64) -> Vec<i64> {
+    let mut stages = vec![];
+    let mut fuel = fuel_needed_for_mass(mass);
+
+    while fuel > 0 {
+        stages.push(fuel);
+        fuel = fuel_needed_for_mass(fuel);
+    }
+
+    stages
+}
+
+/**
+ * Masses below this are looked up in `FUEL_TABLE` rather than worked out.
+ */
+pub const DEFAULT_TABLE_SIZE: usize = 1 << 16;
+
+lazy_static! {
+    static ref FUEL_TABLE: FuelTable = FuelTable::new(DEFAULT_TABLE_SIZE);
+}
+
+/**
+ * Total fuel for every mass below a limit, so a total only needs the few stages it
+ * takes for a big mass to drop under the limit (each stage divides it by three).
+ */
+#[derive(Clone, Debug)]
+pub struct FuelTable {
+    totals: Vec<i64>,
+}
+
+impl FuelTable {
+    pub fn new(size: usize) -> Self {
+        let mut totals = vec![0; size];
+
+        // Fuel is always lighter than its mass, so smaller masses are filled in first.
+        for mass in 0..size {
+            let fuel = fuel_needed_for_mass(mass as i64);
+
+            if fuel > 0 {
+                totals[mass] = fuel + totals[fuel as usize];
+            }
+        }
+
+        FuelTable { totals }
+    }
+
+    pub fn len(&self) -> usize {
+        self.totals.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.totals.is_empty()
+    }
+
+    pub fn total_fuel(&self, mass: i64) -> i64 {
+        let mut total = 0;
+        let mut mass = mass;
+
+        while mass >= self.totals.len() as i64 {
+            let fuel = fuel_needed_for_mass(mass);
+
+            if fuel <= 0 {
+                return total;
+            }
+
+            total += fuel;
+            mass = fuel;
+        }
+
+        // Negative masses need no fuel.
+        if mass < 0 {
+            return total;
+        }
+
+        total + self.totals[mass as usize]
+    }
+
+    pub fn total_fuel_for(&self, masses: &[i64]) -> i64 {
+        masses.iter().map(|mass| self.total_fuel(*mass)).sum()
+    }
+
+    pub fn total_fuel_for_par(&self, masses: &[i64]) -> i64 {
+        masses.par_iter().map(|mass| self.total_fuel(*mass)).sum()
+    }
+}
+
+/**
+ * `total_fuel_needed_for_mass`, using a table shared across calls.
+ */
+pub fn total_fuel_from_table(mass: i64) -> i64 {
+    FUEL_TABLE.total_fuel(mass)
+}
+
+/**
+ * How many fuel stages a model may add before `total_fuel` gives up on it converging.
+ */
+pub const DEFAULT_ITERATION_CAP: usize = 1_000;
+
+#[derive(Debug, PartialEq)]
+pub enum FuelModelError {
+    ZeroDivisor,
+    IterationCap { cap: usize, total: i64 },
+    Overflow { total: i64, fuel: i64 },
+}
+
+impl Error for FuelModelError {}
+
+impl fmt::Display for FuelModelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FuelModelError::ZeroDivisor => {
+                write!(f, "Fuel model divisor must not be zero")
+            }
+            FuelModelError::IterationCap { cap, total } => write!(
+                f,
+                "Fuel did not run out after {} stages (total so far {})",
+                cap, total
+            ),
+            FuelModelError::Overflow { total, fuel } => {
+                write!(f, "Adding {} fuel to {} overflows", fuel, total)
+            }
+        }
+    }
+}
+
+/**
+ * A formula for the fuel needed to launch a mass. The total fuel adds fuel for the
+ * fuel, stage by stage, until a stage needs none.
+ */
+pub trait FuelModel {
+    fn fuel_for(&self, mass: i64) -> i64;
+
+    fn total_fuel(&self, mass: i64) -> Result<i64, FuelModelError> {
+        self.total_fuel_capped(mass, DEFAULT_ITERATION_CAP)
+    }
+
+    fn total_fuel_capped(&self, mass: i64, cap: usize) -> Result<i64, FuelModelError> {
+        let mut total: i64 = 0;
+        let mut fuel = self.fuel_for(mass);
+
+        for _ in 0..cap {
+            if fuel <= 0 {
+                return Ok(total);
+            }
+
+            total = total
+                .checked_add(fuel)
+                .ok_or(FuelModelError::Overflow { total, fuel })?;
+            fuel = self.fuel_for(fuel);
+        }
+
+        if fuel <= 0 {
+            Ok(total)
+        } else {
+            Err(FuelModelError::IterationCap { cap, total })
+        }
+    }
+}
+
+/**
+ * The puzzle's formula: divide by three, round down, subtract two.
+ */
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct RocketEquation;
+
+impl FuelModel for RocketEquation {
+    fn fuel_for(&self, mass: i64) -> i64 {
+        fuel_needed_for_mass(mass)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Rounding {
+    // What integer division does, and so what `fuel_needed_for_mass` does.
+    #[default]
+    TowardZero,
+    Floor,
+    Ceiling,
+    // Halves round away from zero.
+    Nearest,
+}
+
+impl Rounding {
+    pub fn divide(self, n: i128, d: i128) -> i128 {
+        let (q, r) = n.div_mod_floor(&d);
+
+        if r == 0 {
+            return q;
+        }
+
+        match self {
+            Rounding::TowardZero if q < 0 => q + 1,
+            Rounding::TowardZero | Rounding::Floor => q,
+            Rounding::Ceiling => q + 1,
+            Rounding::Nearest => {
+                let (r, rest) = (r.abs(), d.abs() - r.abs());
+
+                if r > rest || (r == rest && q >= 0) {
+                    q + 1
+                } else {
+                    q
+                }
+            }
+        }
+    }
+}
+
+/**
+ * `mass / divisor - offset`, rounded as configured. The default is the rocket
+ * equation.
+ */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LinearModel {
+    divisor: i64,
+    offset: i64,
+    rounding: Rounding,
+}
+
+impl Default for LinearModel {
+    fn default() -> Self {
+        LinearModel {
+            divisor: 3,
+            offset: 2,
+            rounding: Rounding::TowardZero,
+        }
+    }
+}
+
+impl LinearModel {
+    pub fn new(
+        divisor: i64,
+        offset: i64,
+        rounding: Rounding,
+    ) -> Result<Self, FuelModelError> {
+        if divisor == 0 {
+            return Err(FuelModelError::ZeroDivisor);
+        }
+
+        Ok(LinearModel {
+            divisor,
+            offset,
+            rounding,
+        })
+    }
+
+    pub fn divisor(&self) -> i64 {
+        self.divisor
+    }
+
+    pub fn offset(&self) -> i64 {
+        self.offset
+    }
+
+    pub fn rounding(&self) -> Rounding {
+        self.rounding
+    }
+}
+
+impl FuelModel for LinearModel {
+    // Worked in i128 and clamped, so extreme parameters saturate rather than wrap.
+    fn fuel_for(&self, mass: i64) -> i64 {
+        let fuel = self
+            .rounding
+            .divide(i128::from(mass), i128::from(self.divisor))
+            - i128::from(self.offset);
+
+        i64::try_from(fuel).unwrap_or(if fuel < 0 { i64::MIN } else { i64::MAX })
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ManifestErrorKind {
+    NotANumber(String),
+    Negative(i64),
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ManifestError {
+    pub line: usize,
+    pub kind: ManifestErrorKind,
+}
+
+impl Error for ManifestError {}
+
+impl fmt::Display for ManifestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            ManifestErrorKind::NotANumber(text) => {
+                write!(f, "line {}: mass {:?} is not a number", self.line, text)
+            }
+            ManifestErrorKind::Negative(mass) => {
+                write!(f, "line {}: mass {} is negative", self.line, mass)
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Module {
+    line: usize,
+    name: Option<String>,
+    mass: i64,
+}
+
+// Only the parser makes modules, so masses are never negative.
+impl Module {
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    pub fn mass(&self) -> i64 {
+        self.mass
+    }
+
+    pub fn fuel(&self) -> i64 {
+        fuel_needed_for_mass(self.mass).max(0)
+    }
+
+    pub fn stages(&self) -> Vec<i64> {
+        fuel_stages_for_mass(self.mass)
+    }
+
+    pub fn fuel_for_fuel(&self) -> i64 {
+        self.stages().iter().skip(1).sum()
+    }
+
+    pub fn total_fuel(&self) -> i64 {
+        self.stages().iter().sum()
+    }
+}
+
+impl fmt::Display for Module {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match &self.name {
+            Some(name) => name.clone(),
+            None => format!("line {}", self.line),
+        };
+        let stages = self
+            .stages()
+            .iter()
+            .map(|fuel| fuel.to_string())
+            .collect::<Vec<_>>();
+
+        write!(
+            f,
+            "{}: mass {}, fuel {}, fuel for fuel {} ({}), total {}",
+            name,
+            self.mass,
+            self.fuel(),
+            self.fuel_for_fuel(),
+            stages.join(" + "),
+            self.total_fuel()
+        )
+    }
+}
+
+/**
+ * A list of module masses, one per line. A mass may be preceded by a name, and `#`
+ * starts a comment:
+ *
+ * ```text
+ * # Lander
+ * engine 1969
+ * 12          # unnamed
+ * ```
+ */
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ModuleManifest {
+    modules: Vec<Module>,
+}
+
+impl FromStr for ModuleManifest {
+    type Err = ManifestError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut modules = vec![];
+
+        for (i, line) in text.lines().enumerate() {
+            let line_number = i + 1;
+            let content = line.split('#').next().unwrap_or("").trim();
+
+            let mut words = content.split_whitespace().collect::<Vec<_>>();
+
+            let mass_text = match words.pop() {
+                Some(mass_text) => mass_text,
+                None => continue,
+            };
+
+            let mass = mass_text.parse::<i64>().map_err(|_| ManifestError {
+                line: line_number,
+                kind: ManifestErrorKind::NotANumber(mass_text.to_string()),
+            })?;
+
+            if mass < 0 {
+                return Err(ManifestError {
+                    line: line_number,
+                    kind: ManifestErrorKind::Negative(mass),
+                });
+            }
+
+            modules.push(Module {
+                line: line_number,
+                name: if words.is_empty() {
+                    None
+                } else {
+                    Some(words.join(" "))
+                },
+                mass,
+            });
+        }
+
+        Ok(ModuleManifest { modules })
+    }
+}
+
+impl ModuleManifest {
+    pub fn modules(&self) -> &[Module] {
+        &self.modules
+    }
+
+    pub fn len(&self) -> usize {
+        self.modules.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.modules.is_empty()
+    }
+
+    // Totals are summed in a u128, which has room for any manifest of i64 masses.
+    pub fn mass(&self) -> u128 {
+        self.modules.iter().map(|module| wide(module.mass)).sum()
+    }
+
+    pub fn fuel(&self) -> u128 {
+        self.modules.iter().map(|module| wide(module.fuel())).sum()
+    }
+
+    pub fn fuel_for_fuel(&self) -> u128 {
+        self.modules
+            .iter()
+            .map(|module| wide(module.fuel_for_fuel()))
+            .sum()
+    }
+
+    pub fn total_fuel(&self) -> u128 {
+        self.modules
+            .iter()
+            .map(|module| wide(module.total_fuel()))
+            .sum()
+    }
+
+    // For big manifests: a parallel sum using the shared fuel table.
+    pub fn total_fuel_par(&self) -> u128 {
+        self.modules
+            .par_iter()
+            .map(|module| wide(total_fuel_from_table(module.mass)))
+            .sum()
+    }
+
+    pub fn checked_total_fuel(&self) -> Result<i64, FuelError> {
+        checked_total_fuel(self.modules.iter().map(|module| module.mass))
+    }
+
+    pub fn fuel_with(&self, model: &impl FuelModel) -> u128 {
+        self.modules
+            .iter()
+            .map(|module| wide(model.fuel_for(module.mass).max(0)))
+            .sum()
+    }
+
+    // A model can still fail on a single module, but not on the sum.
+    pub fn total_fuel_with(
+        &self,
+        model: &impl FuelModel,
+    ) -> Result<u128, FuelModelError> {
+        self.modules.iter().try_fold(0, |total, module| {
+            Ok(total + wide(model.total_fuel(module.mass)?.max(0)))
+        })
+    }
+}
+
+// The parser rejects negative masses, and fuel is never negative.
+fn wide(value: i64) -> u128 {
+    u128::try_from(value).expect("manifest masses are not negative")
+}
+
+impl fmt::Display for ModuleManifest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for module in &self.modules {
+            writeln!(f, "{}", module)?;
+        }
+
+        write!(
+            f,
+            "{} modules: mass {}, fuel {}, fuel for fuel {}, total {}",
+            self.len(),
+            self.mass(),
+            self.fuel(),
+            self.fuel_for_fuel(),
+            self.total_fuel()
+        )
+    }
+}
+
+pub struct Day01;
+
+impl Solution for Day01 {
+    type Input = ModuleManifest;
+
+    fn parse(input: &str) -> Result<Self::Input, Box<dyn Error>> {
+        Ok(input.parse()?)
+    }
+
+    fn part1(manifest: &Self::Input) -> Answer {
+        Ok(manifest.fuel().to_string())
+    }
+
+    fn part2(manifest: &Self::Input) -> Answer {
+        Ok(manifest.total_fuel().to_string())
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
+    use num::ToPrimitive;
+    use proptest::prelude::*;
 
     #[test]
     fn test_total_fuel_needed_for_mass() {
@@ -41,4 +697,348 @@ mod test {
         assert_eq!(fuel_needed_for_mass(1969), 654);
         assert_eq!(fuel_needed_for_mass(100_756), 33583);
     }
+
+    #[test]
+    fn test_fuel_stages_for_mass() {
+        assert_eq!(fuel_stages_for_mass(14), vec![2]);
+        assert_eq!(fuel_stages_for_mass(1969), vec![654, 216, 70, 21, 5]);
+        assert_eq!(fuel_stages_for_mass(5), vec![]);
+    }
+
+    #[test]
+    fn test_parse_manifest() {
+        let manifest: ModuleManifest =
+            "# Lander\nmain engine 1969\n\n  12   # unnamed\n14"
+                .parse()
+                .unwrap();
+
+        assert_eq!(
+            manifest.modules[0],
+            Module {
+                line: 2,
+                name: Some("main engine".to_string()),
+                mass: 1969
+            }
+        );
+        assert_eq!(
+            manifest.modules[1],
+            Module {
+                line: 4,
+                name: None,
+                mass: 12
+            }
+        );
+        assert_eq!(manifest.len(), 3);
+    }
+
+    #[test]
+    fn test_parse_manifest_errors() {
+        assert_eq!(
+            "12\nfourteen 14\nheavy".parse::<ModuleManifest>(),
+            Err(ManifestError {
+                line: 3,
+                kind: ManifestErrorKind::NotANumber("heavy".to_string())
+            })
+        );
+        assert_eq!(
+            "12\n\nprobe -14 # broken".parse::<ModuleManifest>(),
+            Err(ManifestError {
+                line: 3,
+                kind: ManifestErrorKind::Negative(-14)
+            })
+        );
+        assert_eq!(
+            "1.5".parse::<ModuleManifest>().unwrap_err().to_string(),
+            "line 1: mass \"1.5\" is not a number"
+        );
+    }
+
+    #[test]
+    fn test_manifest_totals() {
+        let manifest: ModuleManifest =
+            "a 12\nb 14\nc 1969\nd 100756\ne 2".parse().unwrap();
+
+        assert_eq!(manifest.mass(), 102_753);
+        assert_eq!(manifest.fuel(), 2 + 2 + 654 + 33583);
+        assert_eq!(manifest.total_fuel(), 2 + 2 + 966 + 50346);
+        assert_eq!(
+            manifest.fuel_for_fuel(),
+            manifest.total_fuel() - manifest.fuel()
+        );
+
+        assert_eq!(
+            manifest.modules[2].to_string(),
+            "c: mass 1969, fuel 654, fuel for fuel 312 (654 + 216 + 70 + 21 + 5), total 966"
+        );
+        assert!(manifest.to_string().ends_with(
+            "5 modules: mass 102753, fuel 34241, fuel for fuel 17075, total 51316"
+        ));
+    }
+
+    #[test]
+    fn test_rounding() {
+        assert_eq!(Rounding::TowardZero.divide(7, 2), 3);
+        assert_eq!(Rounding::TowardZero.divide(-7, 2), -3);
+        assert_eq!(Rounding::Floor.divide(-7, 2), -4);
+        assert_eq!(Rounding::Ceiling.divide(7, 2), 4);
+        assert_eq!(Rounding::Ceiling.divide(-7, 2), -3);
+        assert_eq!(Rounding::Nearest.divide(7, 2), 4);
+        assert_eq!(Rounding::Nearest.divide(-7, 2), -4);
+        assert_eq!(Rounding::Nearest.divide(10, 3), 3);
+        assert_eq!(Rounding::Nearest.divide(11, 3), 4);
+        assert_eq!(Rounding::Nearest.divide(11, -3), -4);
+        assert_eq!(Rounding::Floor.divide(9, 3), 3);
+    }
+
+    #[test]
+    fn test_default_models_match_rocket_equation() {
+        let linear = LinearModel::default();
+
+        for mass in &[-100, -7, 0, 5, 6, 12, 14, 1969, 100_756] {
+            assert_eq!(RocketEquation.fuel_for(*mass), fuel_needed_for_mass(*mass));
+            assert_eq!(linear.fuel_for(*mass), fuel_needed_for_mass(*mass));
+            assert_eq!(
+                linear.total_fuel(*mass),
+                Ok(total_fuel_needed_for_mass(*mass))
+            );
+        }
+    }
+
+    #[test]
+    fn test_linear_model() {
+        assert_eq!(
+            LinearModel::new(0, 2, Rounding::Floor),
+            Err(FuelModelError::ZeroDivisor)
+        );
+
+        let generous = LinearModel::new(3, 2, Rounding::Ceiling).unwrap();
+        assert_eq!(generous.fuel_for(14), 3);
+        assert_eq!(generous.total_fuel(14), Ok(3));
+        assert_eq!(generous.total_fuel(1969), Ok(655 + 217 + 71 + 22 + 6));
+
+        let heavy = LinearModel::new(2, 0, Rounding::Floor).unwrap();
+        assert_eq!(heavy.total_fuel(16), Ok(8 + 4 + 2 + 1));
+    }
+
+    #[test]
+    fn test_total_fuel_iteration_cap() {
+        // Fuel that weighs as much as its payload never runs out.
+        let identity = LinearModel::new(1, 0, Rounding::Floor).unwrap();
+        assert_eq!(
+            identity.total_fuel_capped(10, 5),
+            Err(FuelModelError::IterationCap { cap: 5, total: 50 })
+        );
+
+        let runaway = LinearModel::new(1, i64::MIN, Rounding::Floor).unwrap();
+        assert_eq!(runaway.fuel_for(1), i64::MAX);
+        assert!(matches!(
+            runaway.total_fuel(1),
+            Err(FuelModelError::Overflow { .. })
+        ));
+
+        assert_eq!(RocketEquation.total_fuel_capped(1969, 5), Ok(966));
+        assert!(RocketEquation.total_fuel_capped(1969, 4).is_err());
+    }
+
+    #[test]
+    fn test_manifest_with_model() {
+        let manifest: ModuleManifest = "12\n14\n1969".parse().unwrap();
+
+        assert_eq!(manifest.fuel_with(&RocketEquation), manifest.fuel());
+        assert_eq!(
+            manifest.total_fuel_with(&RocketEquation),
+            Ok(manifest.total_fuel())
+        );
+
+        let generous = LinearModel::new(3, 2, Rounding::Ceiling).unwrap();
+        assert_eq!(manifest.fuel_with(&generous), 2 + 3 + 655);
+    }
+
+    #[test]
+    fn test_fuel_table() {
+        let table = FuelTable::new(100);
+
+        assert_eq!(table.len(), 100);
+        assert_eq!(table.total_fuel(-5), 0);
+        assert_eq!(table.total_fuel(0), 0);
+        assert_eq!(table.total_fuel(14), 2);
+        assert_eq!(table.total_fuel(1969), 966);
+        assert_eq!(table.total_fuel(100_756), 50346);
+        assert_eq!(table.total_fuel_for(&[12, 14, 1969, 100_756]), 51316);
+        assert_eq!(table.total_fuel_for_par(&[12, 14, 1969, 100_756]), 51316);
+
+        assert_eq!(FuelTable::new(0).total_fuel(1969), 966);
+        assert_eq!(total_fuel_from_table(1969), 966);
+    }
+
+    #[test]
+    fn test_manifest_total_fuel_par() {
+        let manifest: ModuleManifest = include_str!("../data/d01.txt").parse().unwrap();
+
+        assert_eq!(manifest.total_fuel_par(), manifest.total_fuel());
+        assert_eq!(manifest.total_fuel_par(), 4_974_428);
+    }
+
+    proptest! {
+        #[test]
+        fn prop_table_matches_loop(mass in any::<i64>()) {
+            prop_assert_eq!(total_fuel_from_table(mass), total_fuel_needed_for_mass(mass));
+        }
+
+        #[test]
+        fn prop_small_masses_match_loop(mass in -1_000i64..1_000, size in 0usize..200) {
+            let table = FuelTable::new(size);
+
+            prop_assert_eq!(table.total_fuel(mass), total_fuel_needed_for_mass(mass));
+        }
+
+        #[test]
+        fn prop_parallel_sum_matches_loop(
+            masses in proptest::collection::vec(-1_000_000_000_000i64..1_000_000_000_000, 0..500)
+        ) {
+            let expected: i64 = masses.iter().cloned().map(total_fuel_needed_for_mass).sum();
+
+            prop_assert_eq!(FUEL_TABLE.total_fuel_for_par(&masses), expected);
+        }
+    }
+
+    #[test]
+    fn test_checked_fuel_needed_for_mass() {
+        assert_eq!(checked_fuel_needed_for_mass(1969), Ok(654));
+        assert_eq!(checked_fuel_needed_for_mass(5), Ok(0));
+        assert_eq!(checked_fuel_needed_for_mass(0), Ok(0));
+        assert_eq!(
+            checked_fuel_needed_for_mass(-1),
+            Err(FuelError::NegativeMass(-1))
+        );
+        assert_eq!(
+            checked_fuel_needed_for_mass(i64::MIN),
+            Err(FuelError::NegativeMass(i64::MIN))
+        );
+        assert_eq!(checked_fuel_needed_for_mass(i64::MAX), Ok(i64::MAX / 3 - 2));
+
+        assert_eq!(checked_total_fuel_needed_for_mass(100_756), Ok(50346));
+        assert_eq!(
+            checked_total_fuel_needed_for_mass(i64::MAX),
+            Ok(total_fuel_needed_for_mass(i64::MAX))
+        );
+    }
+
+    #[test]
+    fn test_checked_totals() {
+        assert_eq!(checked_sum(vec![1, 2, 3]), Some(6));
+        assert_eq!(checked_sum(vec![i64::MAX, 1]), None);
+
+        assert_eq!(checked_total_fuel(vec![12, 14, 1969, 100_756]), Ok(51316));
+        assert_eq!(
+            checked_total_fuel(vec![12, -14]),
+            Err(FuelError::NegativeMass(-14))
+        );
+        assert_eq!(
+            checked_total_fuel(vec![i64::MAX, i64::MAX, i64::MAX]),
+            Err(FuelError::Overflow)
+        );
+    }
+
+    #[test]
+    fn test_wide_totals() {
+        let huge = vec![i64::MAX; 4];
+        let one = u128::from(total_fuel_needed_for_mass(i64::MAX) as u64);
+
+        assert_eq!(total_fuel_wide(huge.clone()), Ok(one * 4));
+        assert_eq!(
+            total_fuel_wide(vec![1969, -1]),
+            Err(FuelError::NegativeMass(-1))
+        );
+
+        let manifest = ModuleManifest {
+            modules: huge
+                .into_iter()
+                .enumerate()
+                .map(|(line, mass)| Module {
+                    line,
+                    name: None,
+                    mass,
+                })
+                .collect(),
+        };
+        assert_eq!(manifest.checked_total_fuel(), Err(FuelError::Overflow));
+        assert_eq!(manifest.total_fuel(), one * 4);
+        assert_eq!(manifest.total_fuel_par(), one * 4);
+        assert_eq!(manifest.mass(), i64::MAX as u128 * 4);
+        assert!(manifest
+            .to_string()
+            .ends_with(&format!("total {}", one * 4)));
+        assert_eq!(manifest.fuel_with(&RocketEquation), manifest.fuel());
+
+        assert_eq!(fuel_needed_for_mass_u128(5), 0);
+        assert_eq!(total_fuel_needed_for_mass_u128(100_756), 50346);
+        assert!(total_fuel_needed_for_mass_u128(u128::MAX) < u128::MAX / 2);
+    }
+
+    #[test]
+    fn test_manifest_totals_do_not_overflow() {
+        let text = format!("a {}\nb {}", i64::MAX, i64::MAX);
+        let manifest: ModuleManifest = text.parse().unwrap();
+        let max = i64::MAX as u128;
+
+        assert_eq!(manifest.mass(), max * 2);
+        assert_eq!(manifest.fuel(), (max / 3 - 2) * 2);
+        assert_eq!(
+            manifest.fuel() + manifest.fuel_for_fuel(),
+            manifest.total_fuel()
+        );
+        assert_eq!(
+            manifest.total_fuel(),
+            total_fuel_wide(vec![i64::MAX; 2]).unwrap()
+        );
+        assert_eq!(
+            manifest.to_string().lines().last(),
+            Some(
+                &format!(
+                    "2 modules: mass {}, fuel {}, fuel for fuel {}, total {}",
+                    max * 2,
+                    manifest.fuel(),
+                    manifest.fuel_for_fuel(),
+                    manifest.total_fuel()
+                )[..]
+            )
+        );
+    }
+
+    #[test]
+    fn test_big_fuel() {
+        assert_eq!(
+            total_fuel_needed_for_mass_big(&BigUint::from(100_756u32)),
+            BigUint::from(50346u32)
+        );
+        assert_eq!(
+            fuel_needed_for_mass_big(&BigUint::from(4u32)),
+            BigUint::from(0u32)
+        );
+
+        let mass = BigUint::from(u128::MAX) * BigUint::from(u128::MAX);
+        let fuel = total_fuel_needed_for_mass_big(&mass);
+
+        assert!(fuel < mass.clone() / 2u32);
+        assert!(fuel > mass / 3u32);
+    }
+
+    proptest! {
+        #[test]
+        fn prop_checked_matches_unchecked(mass in 0i64..=i64::MAX) {
+            prop_assert_eq!(
+                checked_total_fuel_needed_for_mass(mass),
+                Ok(total_fuel_needed_for_mass(mass))
+            );
+            prop_assert_eq!(
+                total_fuel_needed_for_mass_u128(mass as u128).to_i64(),
+                Some(total_fuel_needed_for_mass(mass))
+            );
+            prop_assert_eq!(
+                total_fuel_needed_for_mass_big(&BigUint::from(mass as u64)).to_i64(),
+                Some(total_fuel_needed_for_mass(mass))
+            );
+        }
+    }
 }
@@ -1,29 +1,468 @@
-use std::collections::HashSet;
+use crate::solution::{Answer, Solution};
+use itertools::Itertools;
+use num::Integer;
+use std::cmp::Ordering;
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::convert::TryInto;
-
-#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+use std::error::Error;
+use std::fmt;
+use std::ops::{Add, Mul, Neg, Sub};
+use std::str::FromStr;
+
+/**
+ * A cell, with up towards negative y. Coordinates order by x, then y; use `ReadingOrder`
+ * or `AngleFrom` for the other orders puzzles need.
+ */
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash, Ord, PartialOrd)]
 pub struct Coordinate {
     pub x: isize,
     pub y: isize,
 }
 
+/**
+ * Orders coordinates the way text is read: top row first, then left to right.
+ */
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct ReadingOrder(pub Coordinate);
+
+impl Ord for ReadingOrder {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.0.y, self.0.x).cmp(&(other.0.y, other.0.x))
+    }
+}
+
+impl PartialOrd for ReadingOrder {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/**
+ * Orders coordinates clockwise around an origin, starting straight up, the way a
+ * rotating laser reaches them. Coordinates in the same direction order nearest first;
+ * the origin itself comes before everything.
+ */
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct AngleFrom(pub Coordinate);
+
+impl AngleFrom {
+    /**
+     * Degrees clockwise from up, in `[0, 360)`.
+     */
+    pub fn angle(&self, c: &Coordinate) -> f64 {
+        (c - &self.0).angle()
+    }
+
+    /**
+     * Compares exactly, without going through floating point angles.
+     */
+    pub fn compare(&self, a: &Coordinate, b: &Coordinate) -> Ordering {
+        let (u, v) = (a - &self.0, b - &self.0);
+        let zero = Coordinate::default();
+
+        match (u == zero, v == zero) {
+            (true, true) => return Ordering::Equal,
+            (true, false) => return Ordering::Less,
+            (false, true) => return Ordering::Greater,
+            _ => (),
+        }
+
+        compare_clockwise(&Coordinate::new(0, -1), &u, &v)
+            .then_with(|| u.manhattan_distance().cmp(&v.manhattan_distance()))
+    }
+}
+
+/**
+ * Orders the non-zero vectors `a` and `b` by how far clockwise each is turned from
+ * `start`; `start` itself comes first. Vectors in the same direction are Equal.
+ */
+pub fn compare_clockwise(start: &Coordinate, a: &Coordinate, b: &Coordinate) -> Ordering {
+    let wide = |c: &Coordinate| (c.x as i128, c.y as i128);
+
+    // 0 from `start` until just before its opposite, 1 for the rest.
+    let half = |v: &Coordinate| {
+        let dot = start.x as i128 * v.x as i128 + start.y as i128 * v.y as i128;
+        let turn = cross(wide(start), wide(v));
+
+        if turn > 0 || (turn == 0 && dot > 0) {
+            0
+        } else {
+            1
+        }
+    };
+
+    half(a).cmp(&half(b)).then(0.cmp(&cross(wide(a), wide(b))))
+}
+
+impl From<(isize, isize)> for Coordinate {
+    fn from((x, y): (isize, isize)) -> Self {
+        Coordinate { x, y }
+    }
+}
+
+impl From<Coordinate> for (isize, isize) {
+    fn from(c: Coordinate) -> Self {
+        (c.x, c.y)
+    }
+}
+
+// Implements `op` for every mix of owned and borrowed coordinates.
+macro_rules! coordinate_op {
+    ($trait:ident, $method:ident, $op:tt) => {
+        impl $trait<&Coordinate> for &Coordinate {
+            type Output = Coordinate;
+
+            fn $method(self, other: &Coordinate) -> Coordinate {
+                Coordinate {
+                    x: self.x $op other.x,
+                    y: self.y $op other.y,
+                }
+            }
+        }
+
+        impl $trait<Coordinate> for Coordinate {
+            type Output = Coordinate;
+
+            fn $method(self, other: Coordinate) -> Coordinate {
+                &self $op &other
+            }
+        }
+
+        impl $trait<&Coordinate> for Coordinate {
+            type Output = Coordinate;
+
+            fn $method(self, other: &Coordinate) -> Coordinate {
+                &self $op other
+            }
+        }
+
+        impl $trait<Coordinate> for &Coordinate {
+            type Output = Coordinate;
+
+            fn $method(self, other: Coordinate) -> Coordinate {
+                self $op &other
+            }
+        }
+    };
+}
+
+coordinate_op!(Add, add, +);
+coordinate_op!(Sub, sub, -);
+
+impl Neg for &Coordinate {
+    type Output = Coordinate;
+
+    fn neg(self) -> Coordinate {
+        Coordinate {
+            x: -self.x,
+            y: -self.y,
+        }
+    }
+}
+
+impl Neg for Coordinate {
+    type Output = Coordinate;
+
+    fn neg(self) -> Coordinate {
+        -&self
+    }
+}
+
+impl Mul<isize> for &Coordinate {
+    type Output = Coordinate;
+
+    fn mul(self, factor: isize) -> Coordinate {
+        Coordinate {
+            x: self.x * factor,
+            y: self.y * factor,
+        }
+    }
+}
+
+impl Mul<isize> for Coordinate {
+    type Output = Coordinate;
+
+    fn mul(self, factor: isize) -> Coordinate {
+        &self * factor
+    }
+}
+
 impl Coordinate {
+    pub fn new(x: isize, y: isize) -> Self {
+        Coordinate { x, y }
+    }
+
+    /**
+     * A quarter turn clockwise about the origin, as seen with up towards negative y.
+     */
+    pub fn rotate_right(&self) -> Coordinate {
+        Coordinate {
+            x: -self.y,
+            y: self.x,
+        }
+    }
+
+    pub fn rotate_left(&self) -> Coordinate {
+        Coordinate {
+            x: self.y,
+            y: -self.x,
+        }
+    }
+
+    /**
+     * Rotates by `quarter_turns` clockwise, or counterclockwise when negative.
+     */
+    pub fn rotate(&self, quarter_turns: isize) -> Coordinate {
+        match quarter_turns.rem_euclid(4) {
+            0 => self.clone(),
+            1 => self.rotate_right(),
+            2 => -self,
+            _ => self.rotate_left(),
+        }
+    }
+
+    /**
+     * The four orthogonal neighbours, clockwise from up.
+     */
+    pub fn neighbors4(&self) -> [Coordinate; 4] {
+        [
+            Direction::Up,
+            Direction::Right,
+            Direction::Down,
+            Direction::Left,
+        ]
+        .map(|direction| self + Coordinate::from(direction.delta()))
+    }
+
+    /**
+     * All eight neighbours, clockwise from up.
+     */
+    pub fn neighbors8(&self) -> [Coordinate; 8] {
+        [
+            Direction::Up,
+            Direction::UpRight,
+            Direction::Right,
+            Direction::DownRight,
+            Direction::Down,
+            Direction::DownLeft,
+            Direction::Left,
+            Direction::UpLeft,
+        ]
+        .map(|direction| self + Coordinate::from(direction.delta()))
+    }
+
+    pub fn distance_to(&self, other: &Coordinate, distance: Distance) -> f64 {
+        match distance {
+            Distance::Manhattan => self.manhattan_distance_to(other) as f64,
+            Distance::Chebyshev => self.chebyshev_distance_to(other) as f64,
+            Distance::Euclidean => self.euclidean_distance_to(other),
+        }
+    }
+
     pub fn manhattan_distance(&self) -> usize {
         (self.x.abs() + self.y.abs()).try_into().unwrap()
     }
+
+    pub fn manhattan_distance_to(&self, other: &Coordinate) -> usize {
+        (self.x - other.x).unsigned_abs() + (self.y - other.y).unsigned_abs()
+    }
+
+    pub fn chebyshev_distance_to(&self, other: &Coordinate) -> usize {
+        (self.x - other.x)
+            .unsigned_abs()
+            .max((self.y - other.y).unsigned_abs())
+    }
+
+    // Squared so it stays exact; it orders points the same as the real distance.
+    pub fn squared_distance_to(&self, other: &Coordinate) -> u128 {
+        let (dx, dy) = (
+            (self.x - other.x).unsigned_abs() as u128,
+            (self.y - other.y).unsigned_abs() as u128,
+        );
+
+        dx * dx + dy * dy
+    }
+
+    pub fn euclidean_distance_to(&self, other: &Coordinate) -> f64 {
+        (self.squared_distance_to(other) as f64).sqrt()
+    }
 }
 
-#[derive(Debug, PartialEq)]
+/**
+ * A straight-line measure of how far apart two points are.
+ */
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum Distance {
+    #[default]
+    Manhattan,
+    Chebyshev,
+    Euclidean,
+}
+
+impl Distance {
+    // Orders pairs of points the same as `distance_to`, without rounding.
+    fn exact(self, a: &Coordinate, b: &Coordinate) -> u128 {
+        match self {
+            Distance::Manhattan => a.manhattan_distance_to(b) as u128,
+            Distance::Chebyshev => a.chebyshev_distance_to(b) as u128,
+            Distance::Euclidean => a.squared_distance_to(b),
+        }
+    }
+}
+
+/**
+ * What makes one point of the wires closer than another: how far it is, or how far
+ * the wires travel to reach it.
+ */
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Metric {
+    Distance(Distance),
+    Steps,
+}
+
+impl Default for Metric {
+    fn default() -> Self {
+        Metric::Distance(Distance::default())
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
 pub enum Direction {
     Up,
     Down,
     Left,
+    #[default]
     Right,
+    UpLeft,
+    UpRight,
+    DownLeft,
+    DownRight,
+    // Any other step, written `(dx,dy)`.
+    Vector(isize, isize),
 }
 
-impl Default for Direction {
-    fn default() -> Self {
-        Direction::Right
+impl Direction {
+    /**
+     * The step taken in this direction. Up is towards negative y.
+     */
+    pub fn delta(&self) -> (isize, isize) {
+        match *self {
+            Direction::Up => (0, -1),
+            Direction::Down => (0, 1),
+            Direction::Left => (-1, 0),
+            Direction::Right => (1, 0),
+            Direction::UpLeft => (-1, -1),
+            Direction::UpRight => (1, -1),
+            Direction::DownLeft => (-1, 1),
+            Direction::DownRight => (1, 1),
+            Direction::Vector(dx, dy) => (dx, dy),
+        }
+    }
+
+    /**
+     * The direction taking steps of `delta`, by name when it has one.
+     */
+    pub fn from_delta(delta: (isize, isize)) -> Direction {
+        match delta {
+            (0, -1) => Direction::Up,
+            (0, 1) => Direction::Down,
+            (-1, 0) => Direction::Left,
+            (1, 0) => Direction::Right,
+            (-1, -1) => Direction::UpLeft,
+            (1, -1) => Direction::UpRight,
+            (-1, 1) => Direction::DownLeft,
+            (1, 1) => Direction::DownRight,
+            (dx, dy) => Direction::Vector(dx, dy),
+        }
+    }
+
+    // A quarter turn clockwise: up becomes right.
+    pub fn turn_right(&self) -> Direction {
+        Direction::from_delta(Coordinate::from(self.delta()).rotate_right().into())
+    }
+
+    pub fn turn_left(&self) -> Direction {
+        Direction::from_delta(Coordinate::from(self.delta()).rotate_left().into())
+    }
+
+    fn from_token(token: &str) -> Option<Direction> {
+        match token {
+            "U" => Some(Direction::Up),
+            "D" => Some(Direction::Down),
+            "L" => Some(Direction::Left),
+            "R" => Some(Direction::Right),
+            "NW" => Some(Direction::UpLeft),
+            "NE" => Some(Direction::UpRight),
+            "SW" => Some(Direction::DownLeft),
+            "SE" => Some(Direction::DownRight),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Direction::Up => write!(f, "U"),
+            Direction::Down => write!(f, "D"),
+            Direction::Left => write!(f, "L"),
+            Direction::Right => write!(f, "R"),
+            Direction::UpLeft => write!(f, "NW"),
+            Direction::UpRight => write!(f, "NE"),
+            Direction::DownLeft => write!(f, "SW"),
+            Direction::DownRight => write!(f, "SE"),
+            Direction::Vector(dx, dy) => write!(f, "({},{})", dx, dy),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ParseRouteErrorKind {
+    EmptyPath,
+    UnknownDirection(String),
+    InvalidSteps(String),
+    InvalidVector(String),
+    ZeroVector,
+}
+
+/**
+ * Where a route stopped making sense. `position` counts characters from the start of
+ * the route text.
+ */
+#[derive(Debug, PartialEq)]
+pub struct ParseRouteError {
+    pub position: usize,
+    pub kind: ParseRouteErrorKind,
+}
+
+impl ParseRouteError {
+    fn offset(self, by: usize) -> Self {
+        ParseRouteError {
+            position: self.position + by,
+            ..self
+        }
+    }
+}
+
+impl Error for ParseRouteError {}
+
+impl fmt::Display for ParseRouteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "at position {}: ", self.position)?;
+
+        match &self.kind {
+            ParseRouteErrorKind::EmptyPath => write!(f, "expected a path"),
+            ParseRouteErrorKind::UnknownDirection(text) => {
+                write!(f, "unknown direction {:?}", text)
+            }
+            ParseRouteErrorKind::InvalidSteps(text) => {
+                write!(f, "invalid step count {:?}", text)
+            }
+            ParseRouteErrorKind::InvalidVector(text) => {
+                write!(f, "invalid vector {:?}, expected (dx,dy)", text)
+            }
+            ParseRouteErrorKind::ZeroVector => write!(f, "a vector must move"),
+        }
     }
 }
 
@@ -33,105 +472,458 @@ pub struct Path {
     steps: usize,
 }
 
-impl From<&str> for Path {
-    fn from(text: &str) -> Self {
-        let direction: Direction = match text.chars().next() {
-            Some('U') => Direction::Up,
-            Some('D') => Direction::Down,
-            Some('L') => Direction::Left,
-            Some('R') => Direction::Right,
-            _ => Direction::default(),
+impl FromStr for Path {
+    type Err = ParseRouteError;
+
+    /**
+     * A direction followed by a step count: `U`, `D`, `L`, `R`, `NE`, `NW`, `SE` or
+     * `SW`, or a vector such as `(2,-1)` to move by that much each step.
+     */
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let error = |position: usize, kind: ParseRouteErrorKind| ParseRouteError {
+            position,
+            kind,
         };
 
-        let mut steps: usize = 0;
+        if text.is_empty() {
+            return Err(error(0, ParseRouteErrorKind::EmptyPath));
+        }
 
-        if !text.is_empty() {
-            steps = match (&text[1..]).parse::<usize>() {
-                Ok(num) => num,
-                _ => 0,
+        let (direction, steps_at) = if text.starts_with('(') {
+            let close = text.find(')').ok_or_else(|| {
+                error(0, ParseRouteErrorKind::InvalidVector(text.to_string()))
+            })?;
+            let vector = &text[..=close];
+            let invalid =
+                || error(0, ParseRouteErrorKind::InvalidVector(vector.to_string()));
+
+            let mut parts = vector[1..close].splitn(2, ',');
+            let dx = parts.next().and_then(|dx| dx.trim().parse().ok());
+            let dy = parts.next().and_then(|dy| dy.trim().parse().ok());
+
+            match (dx, dy) {
+                (Some(0), Some(0)) => {
+                    return Err(error(0, ParseRouteErrorKind::ZeroVector))
+                }
+                (Some(dx), Some(dy)) => (Direction::Vector(dx, dy), close + 1),
+                _ => return Err(invalid()),
             }
-        }
+        } else {
+            let letters = text
+                .find(|c: char| !c.is_ascii_alphabetic())
+                .unwrap_or(text.len());
+            let token = &text[..letters];
 
-        Path { direction, steps }
+            let direction = Direction::from_token(token).ok_or_else(|| {
+                error(0, ParseRouteErrorKind::UnknownDirection(token.to_string()))
+            })?;
+
+            (direction, letters)
+        };
+
+        let steps_text = &text[steps_at..];
+        let steps = steps_text.parse::<usize>().map_err(|_| {
+            error(
+                steps_at,
+                ParseRouteErrorKind::InvalidSteps(steps_text.to_string()),
+            )
+        })?;
+
+        Ok(Path { direction, steps })
+    }
+}
+
+impl fmt::Display for Path {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.direction, self.steps)
     }
 }
 
 impl Path {
+    /**
+     * Panics on `Direction::Vector(0, 0)`, which would never get anywhere.
+     */
+    pub fn new(direction: Direction, steps: usize) -> Self {
+        assert!(direction.delta() != (0, 0), "a path's direction must move");
+
+        Path { direction, steps }
+    }
+
+    pub fn direction(&self) -> Direction {
+        self.direction
+    }
+
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
     pub fn coordinates(start_at: &Coordinate, path: &Path) -> Vec<Coordinate> {
-        let mut coordinates: Vec<Coordinate> = Vec::with_capacity(path.steps);
+        let delta = Coordinate::from(path.direction.delta());
+
+        (0..=path.steps as isize)
+            .map(|i| start_at + &delta * i)
+            .collect()
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct Route(Vec<Path>);
+
+// Commas inside a vector's parentheses don't separate paths.
+fn split_paths(text: &str) -> Vec<(usize, &str)> {
+    let mut paths = vec![];
+    let mut depth = 0;
+    let mut start = 0;
+
+    for (i, ch) in text.char_indices() {
+        match ch {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth <= 0 => {
+                paths.push((start, &text[start..i]));
+                start = i + 1;
+            }
+            _ => (),
+        }
+    }
+
+    paths.push((start, &text[start..]));
+
+    paths
+}
+
+impl FromStr for Route {
+    type Err = ParseRouteError;
+
+    /**
+     * Comma-separated paths, like `R8,U5,NE3,(2,-1)4`. Blank text is an empty route.
+     */
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        if text.trim().is_empty() {
+            return Ok(Route::default());
+        }
+
+        split_paths(text)
+            .into_iter()
+            .map(|(start, path_text)| {
+                let trimmed = path_text.trim_start();
+                let offset = start + path_text.len() - trimmed.len();
+
+                trimmed
+                    .trim_end()
+                    .parse::<Path>()
+                    .map_err(|e| e.offset(text[..offset].chars().count()))
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map(Route)
+    }
+}
+
+impl fmt::Display for Route {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let paths = self.0.iter().map(Path::to_string).collect::<Vec<_>>();
+        write!(f, "{}", paths.join(","))
+    }
+}
+
+impl Route {
+    pub fn paths(&self) -> &[Path] {
+        &self.0
+    }
+}
+
+fn cross(a: (i128, i128), b: (i128, i128)) -> i128 {
+    a.0 * b.1 - a.1 * b.0
+}
+
+fn div_ceil(a: i128, b: i128) -> i128 {
+    -Integer::div_floor(&-a, &b)
+}
+
+// Returns (g, x, y) with a * x + b * y == g, the gcd of a and b.
+fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    if b == 0 {
+        (a, 1, 0)
+    } else {
+        let (g, x, y) = extended_gcd(b, a.mod_floor(&b));
+        (g, y, x - Integer::div_floor(&a, &b) * y)
+    }
+}
+
+// The values of m for which lo <= base + step * m <= hi.
+fn solutions_between(base: i128, step: i128, lo: i128, hi: i128) -> (i128, i128) {
+    if step > 0 {
+        (
+            div_ceil(lo - base, step),
+            Integer::div_floor(&(hi - base), &step),
+        )
+    } else {
+        solutions_between(-base, -step, -hi, -lo)
+    }
+}
+
+/**
+ * The cells `start + i * delta` for `i` from 0 to `steps`: one `Path` of a route.
+ * `steps_before` is how many steps the route took to reach `start`.
+ */
+#[derive(Clone, Debug, PartialEq)]
+pub struct Segment {
+    pub start: Coordinate,
+    pub delta: (isize, isize),
+    pub steps: usize,
+    pub steps_before: usize,
+}
+
+impl Segment {
+    pub fn new(start: &Coordinate, path: &Path, steps_before: usize) -> Self {
+        Segment {
+            start: start.clone(),
+            delta: path.direction().delta(),
+            steps: path.steps(),
+            steps_before,
+        }
+    }
+
+    pub fn point(&self, i: usize) -> Coordinate {
+        &self.start + Coordinate::from(self.delta) * i as isize
+    }
+
+    pub fn end(&self) -> Coordinate {
+        self.point(self.steps)
+    }
+
+    // The smallest and largest corners of the box around the segment.
+    pub fn bounds(&self) -> (Coordinate, Coordinate) {
+        let end = self.end();
+
+        (
+            Coordinate {
+                x: self.start.x.min(end.x),
+                y: self.start.y.min(end.y),
+            },
+            Coordinate {
+                x: self.start.x.max(end.x),
+                y: self.start.y.max(end.y),
+            },
+        )
+    }
+
+    fn start_wide(&self) -> (i128, i128) {
+        (self.start.x as i128, self.start.y as i128)
+    }
+
+    fn delta_wide(&self) -> (i128, i128) {
+        (self.delta.0 as i128, self.delta.1 as i128)
+    }
+
+    /**
+     * Which step along the segment lands on `c`, if any.
+     */
+    pub fn index_of(&self, c: &Coordinate) -> Option<usize> {
+        let (sx, sy) = self.start_wide();
+        let (dx, dy) = self.delta_wide();
+        let w = (c.x as i128 - sx, c.y as i128 - sy);
+
+        if cross(w, (dx, dy)) != 0 {
+            return None;
+        }
+
+        let (along, step) = if dx != 0 { (w.0, dx) } else { (w.1, dy) };
 
-        let multiplier: i64 =
-            if [Direction::Right, Direction::Down].contains(&path.direction) {
-                1
+        if along % step != 0 {
+            return None;
+        }
+
+        let i = along / step;
+
+        if i >= 0 && i <= self.steps as i128 {
+            Some(i as usize)
+        } else {
+            None
+        }
+    }
+
+    /**
+     * The first and last steps that land inside the box from `min` to `max`, if
+     * any do.
+     */
+    pub fn indices_within(
+        &self,
+        min: &Coordinate,
+        max: &Coordinate,
+    ) -> Option<(usize, usize)> {
+        let (s, d) = (self.start_wide(), self.delta_wide());
+        let (mut from, mut to) = (0, self.steps as i128);
+
+        for &(start, delta, lo, hi) in &[
+            (s.0, d.0, min.x as i128, max.x as i128),
+            (s.1, d.1, min.y as i128, max.y as i128),
+        ] {
+            if delta == 0 {
+                if start < lo || start > hi {
+                    return None;
+                }
             } else {
-                -1
-            };
-
-        if [Direction::Up, Direction::Down].contains(&path.direction) {
-            // y direction
-            for i in 0..=path.steps {
-                coordinates.push(Coordinate {
-                    y: ((start_at.y as i64) + (multiplier * i as i64)) as isize,
-                    ..*start_at
-                });
+                let (axis_from, axis_to) = solutions_between(start, delta, lo, hi);
+                from = from.max(axis_from);
+                to = to.min(axis_to);
             }
+        }
+
+        if from <= to {
+            Some((from as usize, to as usize))
         } else {
-            // x direction
-            for i in 0..=path.steps {
-                coordinates.push(Coordinate {
-                    x: ((start_at.x as i64) + (multiplier * i as i64)) as isize,
-                    ..*start_at
-                });
+            None
+        }
+    }
+
+    /**
+     * Every pair `(i, j)` where step `i` of this segment and step `j` of `other`
+     * are the same cell.
+     */
+    pub fn crossings(&self, other: &Segment) -> Vec<(usize, usize)> {
+        let d1 = self.delta_wide();
+        let d2 = other.delta_wide();
+        let (s1, s2) = (self.start_wide(), other.start_wide());
+        let w = (s2.0 - s1.0, s2.1 - s1.1);
+        let (n1, n2) = (self.steps as i128, other.steps as i128);
+
+        let denominator = cross(d1, d2);
+
+        if denominator != 0 {
+            let (i, j) = (cross(w, d2), cross(w, d1));
+
+            if i % denominator != 0 || j % denominator != 0 {
+                return vec![];
             }
+
+            let (i, j) = (i / denominator, j / denominator);
+
+            if i < 0 || i > n1 || j < 0 || j > n2 {
+                return vec![];
+            }
+
+            return vec![(i as usize, j as usize)];
         }
 
-        coordinates
+        if cross(w, d1) != 0 {
+            return vec![];
+        }
+
+        // On the same line. Measure everything in steps of the smallest lattice
+        // vector along it, g: this segment visits k1 * i and the other t0 + k2 * j.
+        let g = d1.0.gcd(&d1.1);
+        let g = (d1.0 / g, d1.1 / g);
+        let along = |v: (i128, i128)| if g.0 != 0 { v.0 / g.0 } else { v.1 / g.1 };
+        let (k1, k2, t0) = (along(d1), along(d2), along(w));
+
+        // Solve k1 * i - k2 * j == t0.
+        let (gcd, x, y) = extended_gcd(k1, -k2);
+
+        if t0 % gcd != 0 {
+            return vec![];
+        }
+
+        let (i0, j0) = (x * (t0 / gcd), y * (t0 / gcd));
+        let (i_step, j_step) = (-k2 / gcd, -k1 / gcd);
+
+        let (i_from, i_to) = solutions_between(i0, i_step, 0, n1);
+        let (j_from, j_to) = solutions_between(j0, j_step, 0, n2);
+
+        (i_from.max(j_from)..=i_to.min(j_to))
+            .map(|m| ((i0 + i_step * m) as usize, (j0 + j_step * m) as usize))
+            .collect()
     }
 }
 
-#[derive(Debug, Default, PartialEq)]
-pub struct Route(Vec<Path>);
+// Sweep-and-prune: sweeps the segments by their left edge, keeping the ones still
+// in reach, and only pairs up segments whose boxes overlap.
+fn candidate_pairs(a: &[Segment], b: &[Segment]) -> Vec<(usize, usize)> {
+    let mut events = a
+        .iter()
+        .enumerate()
+        .map(|(i, s)| (s.bounds(), 0, i))
+        .chain(b.iter().enumerate().map(|(i, s)| (s.bounds(), 1, i)))
+        .collect::<Vec<_>>();
 
-impl From<&str> for Route {
-    fn from(text: &str) -> Self {
-        let paths_text: Vec<&str> = text
-            .trim()
-            .split(',')
-            .map(|path_text| path_text.trim())
-            .filter(|path_text| !path_text.is_empty())
-            .collect();
+    events.sort_by_key(|((min, _), _, _)| min.x);
 
-        if paths_text.is_empty() {
-            return Route::default();
+    let mut active: [Vec<(Coordinate, Coordinate, usize)>; 2] = [vec![], vec![]];
+    let mut pairs = vec![];
+
+    for ((min, max), side, i) in events {
+        for list in active.iter_mut() {
+            list.retain(|(_, other_max, _)| other_max.x >= min.x);
+        }
+
+        for (other_min, other_max, j) in &active[1 - side] {
+            if other_min.y <= max.y && min.y <= other_max.y {
+                pairs.push(if side == 0 { (i, *j) } else { (*j, i) });
+            }
         }
 
-        let content: Vec<Path> = paths_text
-            .iter()
-            .map(|path_text| Path::from(&path_text[..]))
-            .collect();
+        active[side].push((min, max, i));
+    }
+
+    pairs
+}
+
+/**
+ * Which visit counts when a wire passes through the same cell more than once.
+ */
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum Visit {
+    #[default]
+    First,
+    Last,
+}
 
-        Route(content)
+/**
+ * A wire leaving cell `at` after `from` steps and coming back to it after `to`.
+ */
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Loop {
+    pub at: Coordinate,
+    pub from: usize,
+    pub to: usize,
+}
+
+impl Loop {
+    pub fn steps(&self) -> usize {
+        self.to - self.from
     }
 }
 
-type CoordinateList = Vec<Coordinate>;
+/**
+ * A cell shared by a group of wires.
+ */
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Crossing {
+    pub wires: Vec<usize>,
+    pub at: Coordinate,
+    pub distance: usize,
+    pub steps: usize,
+}
 
+/**
+ * Wires laid out from a shared origin, or each from its own start.
+ */
 #[derive(Debug, Default)]
 pub struct Grid {
-    coordinates: Vec<CoordinateList>,
-    // coordinate_sets: Vec<HashSet<Coordinate>>,
+    origin: Coordinate,
+    starts: Vec<Coordinate>,
+    wires: Vec<Vec<Segment>>,
 }
 
 impl From<&Vec<Route>> for Grid {
     fn from(routes: &Vec<Route>) -> Self {
-        let content = Vec::with_capacity(routes.len());
-
         let mut grid = Grid {
-            coordinates: content,
+            wires: Vec::with_capacity(routes.len()),
+            ..Default::default()
         };
 
         for route in routes.iter() {
-            grid.add_route(&route);
+            grid.add_route(route);
         }
 
         grid
@@ -139,126 +931,413 @@ impl From<&Vec<Route>> for Grid {
 }
 
 impl Grid {
+    /**
+     * An empty grid whose routes start from `origin` unless told otherwise.
+     */
+    pub fn with_origin(origin: Coordinate) -> Self {
+        Grid {
+            origin,
+            ..Default::default()
+        }
+    }
+
     pub fn count(&self) -> usize {
-        self.coordinates.len()
+        self.wires.len()
     }
 
     // Returns index of newly created route.
     pub fn add_route(&mut self, route: &Route) -> usize {
-        // Initialize new HashSet.
-        let index = self.coordinates.len();
-        self.coordinates.push(vec![]);
+        self.add_route_at(route, self.origin())
+    }
+
+    // Like `add_route`, for a route starting somewhere other than the origin.
+    pub fn add_route_at(&mut self, route: &Route, start: Coordinate) -> usize {
+        let mut segments = Vec::with_capacity(route.paths().len());
+        let mut start_at = start.clone();
+        let mut steps = 0;
+
+        for path in route.paths() {
+            if path.steps() == 0 {
+                continue;
+            }
+
+            let segment = Segment::new(&start_at, path, steps);
+            start_at = segment.end();
+            steps += segment.steps;
+            segments.push(segment);
+        }
+
+        self.starts.push(start);
+        self.wires.push(segments);
+
+        self.wires.len() - 1
+    }
+
+    pub fn start(&self, wire: usize) -> &Coordinate {
+        &self.starts[wire]
+    }
+
+    pub fn segments(&self, wire: usize) -> &[Segment] {
+        &self.wires[wire]
+    }
+
+    /**
+     * How many steps `wire` takes to reach `c`, on its first or its last visit.
+     */
+    pub fn steps_to(&self, wire: usize, c: &Coordinate, visit: Visit) -> Option<usize> {
+        let steps =
+            |segment: &Segment| segment.index_of(c).map(|i| segment.steps_before + i);
+
+        // Steps only grow along a wire, and a segment never visits a cell twice, so
+        // the first (or last) segment through `c` holds the visit.
+        let found = match visit {
+            Visit::First => self.wires[wire].iter().find_map(steps),
+            Visit::Last => self.wires[wire].iter().rev().find_map(steps),
+        };
+
+        match found {
+            None if c == self.start(wire) => Some(0),
+            found => found,
+        }
+    }
+
+    pub fn first_visit(&self, wire: usize, c: &Coordinate) -> Option<usize> {
+        self.steps_to(wire, c, Visit::First)
+    }
+
+    pub fn last_visit(&self, wire: usize, c: &Coordinate) -> Option<usize> {
+        self.steps_to(wire, c, Visit::Last)
+    }
+
+    /**
+     * Every cell of `wire` with the steps taken to reach it. This holds one entry
+     * per cell, so prefer `steps_to` for long wires.
+     */
+    pub fn step_map(&self, wire: usize, visit: Visit) -> HashMap<Coordinate, usize> {
+        let mut steps = HashMap::new();
+        steps.insert(self.start(wire).clone(), 0);
+
+        for segment in &self.wires[wire] {
+            for i in 0..=segment.steps {
+                let entry = steps
+                    .entry(segment.point(i))
+                    .or_insert(segment.steps_before + i);
+
+                if visit == Visit::Last {
+                    *entry = segment.steps_before + i;
+                }
+            }
+        }
+
+        steps
+    }
+
+    /**
+     * The cells `wire` passes through more than once, with the steps of every
+     * visit in order.
+     */
+    pub fn revisits(&self, wire: usize) -> HashMap<Coordinate, Vec<usize>> {
+        let segments = &self.wires[wire];
+        let mut visits: HashMap<Coordinate, BTreeSet<usize>> = HashMap::new();
+
+        for (p, q) in candidate_pairs(segments, segments) {
+            if p >= q {
+                continue;
+            }
+
+            let (first, second) = (&segments[p], &segments[q]);
+
+            for (i, j) in first.crossings(second) {
+                let steps = (first.steps_before + i, second.steps_before + j);
+
+                // Consecutive segments share the corner between them.
+                if steps.0 == steps.1 {
+                    continue;
+                }
+
+                let entry = visits.entry(first.point(i)).or_default();
+                entry.insert(steps.0);
+                entry.insert(steps.1);
+            }
+        }
+
+        visits
+            .into_iter()
+            .map(|(c, steps)| (c, steps.into_iter().collect()))
+            .collect()
+    }
+
+    pub fn self_intersections(&self, wire: usize) -> HashSet<Coordinate> {
+        self.revisits(wire).into_keys().collect()
+    }
+
+    /**
+     * How many times `wire` comes back to each cell after its first visit.
+     */
+    pub fn revisit_counts(&self, wire: usize) -> HashMap<Coordinate, usize> {
+        self.revisits(wire)
+            .into_iter()
+            .map(|(c, steps)| (c, steps.len() - 1))
+            .collect()
+    }
+
+    /**
+     * The loops `wire` makes between consecutive visits to the same cell, shortest
+     * first.
+     */
+    pub fn loops(&self, wire: usize) -> Vec<Loop> {
+        let mut loops = self
+            .revisits(wire)
+            .into_iter()
+            .flat_map(|(at, steps)| {
+                steps
+                    .windows(2)
+                    .map(|pair| Loop {
+                        at: at.clone(),
+                        from: pair[0],
+                        to: pair[1],
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
 
-        // println!("route: {:?}", route);
+        loops.sort_by_key(|l| (l.steps(), l.from));
 
-        let mut coordinates: Vec<Coordinate>;
-        let mut coords: &Vec<Coordinate>;
+        loops
+    }
 
-        let origin = self.origin();
+    /**
+     * Where wires `a` and `b` cross, with the fewest steps each takes to get there.
+     * A start they share doesn't count.
+     */
+    pub fn crossings(&self, a: usize, b: usize) -> HashMap<Coordinate, (usize, usize)> {
+        self.crossings_with(a, b, Visit::First)
+    }
 
-        let mut start_at: &Coordinate = &origin;
+    /**
+     * Like `crossings`, with the steps taken on each wire's first or last visit.
+     */
+    pub fn crossings_with(
+        &self,
+        a: usize,
+        b: usize,
+        visit: Visit,
+    ) -> HashMap<Coordinate, (usize, usize)> {
+        let (wire_a, wire_b) = (&self.wires[a], &self.wires[b]);
+        let shared_start = Some(self.start(a)).filter(|start| *start == self.start(b));
+        let mut crossings: HashMap<Coordinate, (usize, usize)> = HashMap::new();
+
+        for (p, q) in candidate_pairs(wire_a, wire_b) {
+            let (segment_a, segment_b) = (&wire_a[p], &wire_b[q]);
+
+            for (i, j) in segment_a.crossings(segment_b) {
+                let c = segment_a.point(i);
+
+                if shared_start == Some(&c) {
+                    continue;
+                }
+
+                let steps = (segment_a.steps_before + i, segment_b.steps_before + j);
+                let entry = crossings.entry(c).or_insert(steps);
+
+                *entry = match visit {
+                    Visit::First => (entry.0.min(steps.0), entry.1.min(steps.1)),
+                    Visit::Last => (entry.0.max(steps.0), entry.1.max(steps.1)),
+                };
+            }
+        }
 
-        self.coordinates[index].push(origin.clone());
+        crossings
+    }
 
-        let mut path_count = 0;
+    /**
+     * The cells every wire passes through, other than a start they all share. With
+     * fewer than two wires nothing crosses.
+     */
+    pub fn intersection(&self) -> HashSet<Coordinate> {
+        if self.count() < 2 {
+            return HashSet::new();
+        }
 
-        for path in route.0.iter() {
-            coordinates = Path::coordinates(start_at, path);
+        self.crossings(0, 1)
+            .into_keys()
+            .filter(|c| (2..self.count()).all(|wire| self.first_visit(wire, c).is_some()))
+            .collect()
+    }
 
-            if coordinates.len() < 2 {
-                break;
-            }
+    pub fn origin(&self) -> Coordinate {
+        self.origin.clone()
+    }
 
-            coords = &coordinates;
+    pub fn closest_to_origin_in_intersection(&self) -> Option<Coordinate> {
+        self.closest_in_intersection(&self.origin(), Metric::default())
+    }
 
-            // Skip first coordinate since it will already be there.
-            for c in coords[1..].to_owned() {
-                path_count += 1;
-                self.coordinates[index].push(c);
-            }
+    /**
+     * The combined steps every wire takes to reach `c`, if they all do.
+     */
+    pub fn steps_to_all(&self, c: &Coordinate) -> Option<usize> {
+        (0..self.count())
+            .map(|wire| self.first_visit(wire, c))
+            .sum()
+    }
 
-            let last_coordinate = match coords.iter().last() {
-                Some(c) => c,
-                _ => break,
-            };
+    /**
+     * The point of the intersection closest to `from`. `Metric::Steps` ignores `from`
+     * and counts the steps the wires take from their starts.
+     */
+    pub fn closest_in_intersection(
+        &self,
+        from: &Coordinate,
+        metric: Metric,
+    ) -> Option<Coordinate> {
+        let distance = |c: &Coordinate| match metric {
+            Metric::Distance(distance) => distance.exact(c, from),
+            Metric::Steps => self
+                .steps_to_all(c)
+                .map_or(u128::MAX, |steps| steps as u128),
+        };
 
-            start_at = last_coordinate;
-        }
+        self.intersection()
+            .into_iter()
+            .min_by_key(|c| (distance(c), c.x, c.y))
+    }
 
-        // The number of coordinates should always be one more than the number of paths.
-        assert_eq!(path_count, self.coordinates[index].len() - 1);
+    pub fn intersection_shortest_path(&self) -> usize {
+        self.intersection_shortest_path_with(Visit::First)
+    }
 
-        // println!("self.coordinates[{:?}]: {:?}", index, &self.coordinates[index][0..10]);
-        // println!("self.coordinates[{:?}].len(): {:?}", index, &self.coordinates[index].len());
+    /**
+     * The fewest combined steps to a crossing, counting each wire's first or last
+     * visit to it.
+     */
+    pub fn intersection_shortest_path_with(&self, visit: Visit) -> usize {
+        let crossings = if self.count() < 2 {
+            HashMap::new()
+        } else {
+            self.crossings_with(0, 1, visit)
+        };
 
-        index
+        crossings
+            .into_iter()
+            .filter_map(|(c, (a, b))| {
+                (2..self.count())
+                    .map(|wire| self.steps_to(wire, &c, visit))
+                    .sum::<Option<usize>>()
+                    .map(|rest| a + b + rest)
+            })
+            .min()
+            .expect("Expect a usize path length")
     }
 
-    pub fn intersection(&self) -> HashSet<Coordinate> {
-        let content = self.coordinates.clone();
+    /**
+     * The crossings shared by all of `wires`, nearest to the origin first. Steps
+     * add up each wire's steps to the crossing from its start.
+     */
+    pub fn crossings_among(&self, wires: &[usize], visit: Visit) -> Vec<Crossing> {
+        if wires.len() < 2 {
+            return vec![];
+        }
 
-        let mut sets = content
+        let mut crossings = self
+            .crossings_with(wires[0], wires[1], visit)
             .into_iter()
-            .map(|coordinates| coordinates.into_iter().collect::<HashSet<Coordinate>>())
-            .collect::<Vec<HashSet<Coordinate>>>()
-            .into_iter();
-
-        let mut result = sets
-            .next()
-            .map(|set| {
-                sets.fold(set, |set1, set2| {
-                    set1.intersection(&set2).cloned().collect()
+            .filter_map(|(at, (a, b))| {
+                let rest = wires[2..]
+                    .iter()
+                    .map(|wire| self.steps_to(*wire, &at, visit))
+                    .sum::<Option<usize>>()?;
+
+                Some(Crossing {
+                    wires: wires.to_vec(),
+                    distance: at.manhattan_distance_to(&self.origin),
+                    steps: a + b + rest,
+                    at,
                 })
             })
-            .expect("No HashSet found");
+            .collect::<Vec<_>>();
 
-        // Remove origin.
-        result.remove(&self.origin());
+        crossings.sort_by_key(|c| (c.distance, c.steps, c.at.x, c.at.y));
 
-        result
+        crossings
     }
 
-    pub fn origin(&self) -> Coordinate {
-        Default::default()
+    /**
+     * The crossings of every group of `k` wires, group by group.
+     */
+    pub fn crossing_report(&self, k: usize) -> Vec<Crossing> {
+        (0..self.count())
+            .combinations(k)
+            .flat_map(|wires| self.crossings_among(&wires, Visit::First))
+            .collect()
     }
 
-    pub fn closest_to_origin_in_intersection(&self) -> Option<Coordinate> {
-        let intersection = &self.intersection();
-
-        intersection
-            .iter()
-            .min_by(|c1, c2| c1.manhattan_distance().cmp(&c2.manhattan_distance()))
-            .cloned()
+    pub fn pairwise_crossings(&self) -> Vec<Crossing> {
+        self.crossing_report(2)
     }
 
-    pub fn intersection_shortest_path(&self) -> usize {
-        // Get all intersections.
-        let intersection = self.intersection();
-        let intersection_vec = intersection.into_iter().collect::<Vec<Coordinate>>();
+    /**
+     * The closest crossing of any `k` wires, by distance from the origin or by steps.
+     * Ties go to whichever is closer by the other measure.
+     */
+    pub fn closest_crossing(&self, k: usize, metric: Metric) -> Option<Crossing> {
+        let key = |crossing: &Crossing| match metric {
+            Metric::Distance(distance) => (
+                distance.exact(&crossing.at, &self.origin),
+                crossing.steps as u128,
+            ),
+            Metric::Steps => (crossing.steps as u128, crossing.distance as u128),
+        };
 
-        intersection_vec
+        self.crossing_report(k)
             .into_iter()
-            .map(|coordinate| {
-                // println!("coordinate: {:?}", coordinate);
+            .min_by_key(|crossing| key(crossing))
+    }
+}
 
-                self.coordinates
-                    .iter()
-                    .map(|coordinate_list| {
-                        coordinate_list
-                            .iter()
-                            .position(|c| *c == coordinate)
-                            .expect("Should find coordinate")
-                    })
-                    .sum::<usize>()
+pub struct Day03;
+
+impl Solution for Day03 {
+    type Input = Grid;
+
+    fn parse(input: &str) -> Result<Self::Input, Box<dyn Error>> {
+        let routes = input
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .enumerate()
+            .map(|(i, line)| {
+                line.parse::<Route>()
+                    .map_err(|e| format!("Route {} {}", i + 1, e))
             })
-            .min()
-            .expect("Expect a usize path length")
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if routes.len() < 2 {
+            return Err("Expected at least two routes".into());
+        }
+
+        Ok(Grid::from(&routes))
+    }
+
+    fn part1(grid: &Self::Input) -> Answer {
+        grid.closest_to_origin_in_intersection()
+            .map(|c| c.manhattan_distance().to_string())
+            .ok_or_else(|| "Routes do not cross".into())
+    }
+
+    fn part2(grid: &Self::Input) -> Answer {
+        if grid.intersection().is_empty() {
+            return Err("Routes do not cross".into());
+        }
+
+        Ok(grid.intersection_shortest_path().to_string())
     }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use proptest::prelude::*;
 
     #[test]
     fn test_default_coordinate() {
@@ -266,24 +1345,72 @@ mod test {
     }
 
     #[test]
-    fn test_path_from_text() {
-        assert_eq!(Path::from(""), Path::default());
+    #[should_panic(expected = "must move")]
+    fn test_path_must_move() {
+        let _ = Path::new(Direction::Vector(0, 0), 3);
+    }
+
+    #[test]
+    fn test_parse_day03() {
+        let grid = Day03::parse("R8, U5,L5,D3\n\n  (0, 1)7 , R6,D4,L4  \n").unwrap();
+
+        assert_eq!(grid.count(), 2);
+        assert_eq!(grid.segments(0).len(), 4);
+        assert_eq!(grid.segments(1)[0].delta, (0, 1));
+        assert!(Day03::parse("R8,U5 L5").is_err());
+    }
+
+    fn route(text: &str) -> Route {
+        text.parse().unwrap()
+    }
 
+    #[test]
+    fn test_parse_path() {
         assert_eq!(
-            Path::from("L1005"),
-            Path {
+            "L1005".parse::<Path>(),
+            Ok(Path {
                 direction: Direction::Left,
                 steps: 1005
-            }
+            })
+        );
+        assert_eq!("NE3".parse::<Path>(), Ok(Path::new(Direction::UpRight, 3)));
+        assert_eq!(
+            "(2,-1)4".parse::<Path>(),
+            Ok(Path::new(Direction::Vector(2, -1), 4))
+        );
+    }
+
+    #[test]
+    fn test_parse_path_errors() {
+        let kind = |text: &str| text.parse::<Path>().unwrap_err().kind;
+
+        assert_eq!(kind(""), ParseRouteErrorKind::EmptyPath);
+        assert_eq!(
+            kind("X5"),
+            ParseRouteErrorKind::UnknownDirection("X".to_string())
+        );
+        assert_eq!(kind("U"), ParseRouteErrorKind::InvalidSteps("".to_string()));
+        assert_eq!(
+            kind("U-3"),
+            ParseRouteErrorKind::InvalidSteps("-3".to_string())
+        );
+        assert_eq!(
+            kind("(1,x)2"),
+            ParseRouteErrorKind::InvalidVector("(1,x)".to_string())
+        );
+        assert_eq!(
+            kind("(1,1"),
+            ParseRouteErrorKind::InvalidVector("(1,1".to_string())
         );
+        assert_eq!(kind("(0,0)2"), ParseRouteErrorKind::ZeroVector);
     }
 
     #[test]
-    fn test_route_from_text() {
-        assert_eq!(Route::from(""), Route::default());
+    fn test_parse_route() {
+        assert_eq!("".parse::<Route>(), Ok(Route::default()));
 
         assert_eq!(
-            Route::from("L2, U5"),
+            route("L2, U5"),
             Route(vec![
                 Path {
                     direction: Direction::Left,
@@ -295,6 +1422,60 @@ mod test {
                 },
             ])
         );
+
+        assert_eq!(
+            route("R1,(1,-1)2,SW3"),
+            Route(vec![
+                Path::new(Direction::Right, 1),
+                Path::new(Direction::Vector(1, -1), 2),
+                Path::new(Direction::DownLeft, 3),
+            ])
+        );
+
+        assert_eq!(route("R1, (1,-1)2,SW3").to_string(), "R1,(1,-1)2,SW3");
+    }
+
+    #[test]
+    fn test_parse_route_errors() {
+        assert_eq!(
+            "R8,U5,Q5".parse::<Route>(),
+            Err(ParseRouteError {
+                position: 6,
+                kind: ParseRouteErrorKind::UnknownDirection("Q".to_string())
+            })
+        );
+
+        let error = "R8, U5x".parse::<Route>().unwrap_err();
+        assert_eq!(error.position, 5);
+        assert_eq!(
+            error.to_string(),
+            "at position 5: invalid step count \"5x\""
+        );
+
+        assert_eq!("R8,,U5".parse::<Route>().unwrap_err().position, 3);
+    }
+
+    #[test]
+    fn test_diagonal_coordinates() {
+        assert_eq!(
+            Path::coordinates(
+                &Coordinate { x: 1, y: 1 },
+                &Path::new(Direction::UpLeft, 2)
+            ),
+            vec![
+                Coordinate { x: 1, y: 1 },
+                Coordinate { x: 0, y: 0 },
+                Coordinate { x: -1, y: -1 }
+            ]
+        );
+
+        let grid = Grid::from(&vec![route("SE4"), route("R4,SW2")]);
+
+        assert_eq!(
+            grid.intersection().into_iter().collect::<Vec<_>>(),
+            vec![Coordinate { x: 2, y: 2 }]
+        );
+        assert_eq!(grid.intersection_shortest_path(), 2 + 6);
     }
 
     #[test]
@@ -305,8 +1486,8 @@ mod test {
     #[test]
     fn test_grid_intersection() {
         let routes = vec![
-            Route::from("L2, U10"),
-            Route::from("U2, L3, U2, R3, U2, L3, D200, R500"),
+            route("L2, U10"),
+            route("U2, L3, U2, R3, U2, L3, D200, R500"),
         ];
 
         let grid = Grid::from(&routes);
@@ -332,12 +1513,502 @@ mod test {
     #[test]
     fn test_grid_intersection_shortest_path() {
         let routes = vec![
-            Route::from("L2, U10"),
-            Route::from("U2, L3, U2, R3, U2, L3, D200, R500"),
+            route("L2, U10"),
+            route("U2, L3, U2, R3, U2, L3, D200, R500"),
         ];
 
         let grid = Grid::from(&routes);
 
         assert_eq!(grid.intersection_shortest_path(), 8);
     }
+
+    fn segment(x: isize, y: isize, direction: Direction, steps: usize) -> Segment {
+        Segment::new(&Coordinate { x, y }, &Path::new(direction, steps), 0)
+    }
+
+    // Brute force over both segments' cells.
+    fn naive_crossings(a: &Segment, b: &Segment) -> Vec<(usize, usize)> {
+        let mut result = vec![];
+
+        for i in 0..=a.steps {
+            for j in 0..=b.steps {
+                if a.point(i) == b.point(j) {
+                    result.push((i, j));
+                }
+            }
+        }
+
+        result
+    }
+
+    #[test]
+    fn test_segment_index_of() {
+        let s = segment(1, 1, Direction::Vector(2, -1), 3);
+
+        assert_eq!(s.end(), Coordinate { x: 7, y: -2 });
+        assert_eq!(s.index_of(&Coordinate { x: 5, y: -1 }), Some(2));
+        assert_eq!(s.index_of(&Coordinate { x: 4, y: -1 }), None);
+        assert_eq!(s.index_of(&Coordinate { x: 9, y: -3 }), None);
+        assert_eq!(s.index_of(&Coordinate { x: -1, y: 2 }), None);
+    }
+
+    #[test]
+    fn test_segment_crossings() {
+        let cases = vec![
+            // Perpendicular.
+            (
+                segment(0, 0, Direction::Right, 8),
+                segment(3, -2, Direction::Down, 5),
+            ),
+            (
+                segment(0, 0, Direction::Right, 8),
+                segment(9, -2, Direction::Down, 5),
+            ),
+            // Diagonals crossing between cells don't meet.
+            (
+                segment(0, 0, Direction::DownRight, 4),
+                segment(1, 0, Direction::DownLeft, 1),
+            ),
+            (
+                segment(0, 0, Direction::DownRight, 4),
+                segment(4, 0, Direction::DownLeft, 4),
+            ),
+            // Overlapping, in the same and in opposite directions.
+            (
+                segment(0, 0, Direction::Right, 8),
+                segment(3, 0, Direction::Right, 10),
+            ),
+            (
+                segment(0, 0, Direction::Right, 8),
+                segment(6, 0, Direction::Left, 10),
+            ),
+            (
+                segment(0, 0, Direction::Right, 8),
+                segment(0, 1, Direction::Right, 8),
+            ),
+            // Vectors that only share some of their cells.
+            (
+                segment(0, 0, Direction::Vector(2, 0), 9),
+                segment(1, 0, Direction::Vector(3, 0), 6),
+            ),
+            (
+                segment(0, 0, Direction::Vector(4, 2), 5),
+                segment(20, 10, Direction::Vector(-6, -3), 4),
+            ),
+            (
+                segment(0, 0, Direction::Vector(2, 0), 9),
+                segment(1, 0, Direction::Vector(2, 0), 6),
+            ),
+            (
+                segment(0, 0, Direction::Vector(1, 2), 5),
+                segment(5, 0, Direction::Vector(-1, 3), 5),
+            ),
+        ];
+
+        for (a, b) in cases {
+            let mut crossings = a.crossings(&b);
+            crossings.sort();
+
+            assert_eq!(crossings, naive_crossings(&a, &b), "{:?} and {:?}", a, b);
+        }
+    }
+
+    #[test]
+    fn test_crossings_use_first_visit() {
+        // The second wire passes (2, 0) twice; its first visit is after 4 steps.
+        let grid = Grid::from(&vec![route("R5"), route("D1,R2,U2,D1,R4,L3,U1,D1")]);
+
+        let crossings = grid.crossings(0, 1);
+
+        assert_eq!(crossings[&Coordinate { x: 2, y: 0 }], (2, 4));
+        assert_eq!(crossings[&Coordinate { x: 5, y: 0 }], (5, 9));
+        assert_eq!(grid.first_visit(1, &Coordinate { x: 3, y: 0 }), Some(7));
+    }
+
+    #[test]
+    fn test_three_wires() {
+        let grid = Grid::from(&vec![route("R8"), route("D1,R3,U2"), route("R3,U1")]);
+
+        assert_eq!(
+            grid.intersection().into_iter().collect::<Vec<_>>(),
+            vec![Coordinate { x: 3, y: 0 }]
+        );
+        assert_eq!(grid.intersection_shortest_path(), 3 + 5 + 3);
+    }
+
+    #[test]
+    fn test_long_routes() {
+        let grid = Grid::from(&vec![
+            route("R10000000,U5000000"),
+            route("U1000000,R20000000,D3000000"),
+        ]);
+
+        let c = grid.closest_to_origin_in_intersection().unwrap();
+
+        assert_eq!(
+            c,
+            Coordinate {
+                x: 10_000_000,
+                y: -1_000_000
+            }
+        );
+        assert_eq!(grid.intersection_shortest_path(), 11_000_000 + 11_000_000);
+    }
+
+    #[test]
+    fn test_revisits() {
+        let grid = Grid::from(&vec![route("R4,D2,L2,U4,R1,D2,R3")]);
+
+        let mut revisits = grid.revisits(0).into_iter().collect::<Vec<_>>();
+        revisits.sort_by_key(|(c, _)| c.x);
+
+        assert_eq!(
+            revisits,
+            vec![
+                (Coordinate { x: 2, y: 0 }, vec![2, 10]),
+                (Coordinate { x: 3, y: 0 }, vec![3, 15]),
+                (Coordinate { x: 4, y: 0 }, vec![4, 16]),
+            ]
+        );
+        assert_eq!(grid.self_intersections(0).len(), 3);
+        assert_eq!(grid.revisit_counts(0)[&Coordinate { x: 3, y: 0 }], 1);
+
+        let loops = grid.loops(0);
+        assert_eq!(loops.len(), 3);
+        assert_eq!(
+            loops[0],
+            Loop {
+                at: Coordinate { x: 2, y: 0 },
+                from: 2,
+                to: 10
+            }
+        );
+        assert_eq!(loops[2].steps(), 12);
+    }
+
+    #[test]
+    fn test_revisit_origin_and_counts() {
+        let grid = Grid::from(&vec![route("R2,L4,R2,U1,D2")]);
+
+        let revisits = grid.revisits(0);
+
+        assert_eq!(revisits[&Coordinate { x: 0, y: 0 }], vec![0, 4, 8, 10]);
+        assert_eq!(grid.revisit_counts(0)[&Coordinate { x: 0, y: 0 }], 3);
+        assert_eq!(grid.last_visit(0, &Coordinate { x: 0, y: 0 }), Some(10));
+        assert!(Grid::from(&vec![route("R2,U2")]).revisits(0).is_empty());
+    }
+
+    #[test]
+    fn test_visit() {
+        let grid = Grid::from(&vec![route("R4,D2,L2,U4,R1,D2,R3"), route("D1,R3,U2")]);
+        let c = Coordinate { x: 3, y: 0 };
+
+        assert_eq!(grid.first_visit(0, &c), Some(3));
+        assert_eq!(grid.last_visit(0, &c), Some(15));
+        assert_eq!(grid.step_map(0, Visit::First)[&c], 3);
+        assert_eq!(grid.step_map(0, Visit::Last)[&c], 15);
+        assert_eq!(grid.step_map(0, Visit::Last).len(), 16);
+        assert_eq!(grid.crossings_with(0, 1, Visit::Last)[&c], (15, 5));
+
+        assert_eq!(grid.intersection_shortest_path_with(Visit::First), 8);
+        assert_eq!(grid.intersection_shortest_path_with(Visit::Last), 12);
+        assert_eq!(grid.intersection_shortest_path(), 8);
+    }
+
+    #[test]
+    fn test_crossing_report() {
+        // Only the first two wires cross away from the third.
+        let grid = Grid::from(&vec![route("R8,U5"), route("U2,R10"), route("D1,R3,U6")]);
+
+        let pairs = grid.pairwise_crossings();
+
+        assert_eq!(
+            pairs,
+            vec![
+                Crossing {
+                    wires: vec![0, 1],
+                    at: Coordinate { x: 8, y: -2 },
+                    distance: 10,
+                    steps: 20
+                },
+                Crossing {
+                    wires: vec![0, 2],
+                    at: Coordinate { x: 3, y: 0 },
+                    distance: 3,
+                    steps: 8
+                },
+                Crossing {
+                    wires: vec![1, 2],
+                    at: Coordinate { x: 3, y: -2 },
+                    distance: 5,
+                    steps: 12
+                },
+            ]
+        );
+
+        assert!(grid.crossing_report(3).is_empty());
+        assert!(grid.intersection().is_empty());
+
+        let closest = grid.closest_crossing(2, Metric::default()).unwrap();
+        assert_eq!(closest.wires, vec![0, 2]);
+        assert_eq!(grid.closest_crossing(2, Metric::Steps), Some(closest));
+    }
+
+    #[test]
+    fn test_closest_crossing_by_steps() {
+        let grid = Grid::from(&vec![route("R8,U5,L5,D3"), route("U7,R6,D4,L4")]);
+
+        let by_distance = grid.closest_crossing(2, Metric::default()).unwrap();
+        let by_steps = grid.closest_crossing(2, Metric::Steps).unwrap();
+
+        assert_eq!(by_distance.at, Coordinate { x: 3, y: -3 });
+        assert_eq!(by_distance.distance, 6);
+        assert_eq!(by_steps.at, Coordinate { x: 6, y: -5 });
+        assert_eq!(by_steps.steps, 30);
+        assert_eq!(
+            grid.closest_crossing(2, Metric::Distance(Distance::Chebyshev)),
+            Some(by_distance)
+        );
+        assert_eq!(grid.crossing_report(2).len(), 2);
+        assert!(grid.closest_crossing(3, Metric::Steps).is_none());
+    }
+
+    #[test]
+    fn test_coordinate_ops() {
+        let (a, b) = (Coordinate::new(3, -2), Coordinate::new(-1, 5));
+
+        assert_eq!(&a + &b, Coordinate::new(2, 3));
+        assert_eq!(a.clone() - b.clone(), Coordinate::new(4, -7));
+        assert_eq!(&a - b.clone(), a.clone() - &b);
+        assert_eq!(-&a, Coordinate::new(-3, 2));
+        assert_eq!(&a * 3, Coordinate::new(9, -6));
+        assert_eq!(a.clone() * -1, -a.clone());
+        assert_eq!(a.clone() + Coordinate::default(), a);
+    }
+
+    #[test]
+    fn test_rotate() {
+        let up = Coordinate::from(Direction::Up.delta());
+        let c = Coordinate::new(3, -2);
+
+        assert_eq!(
+            up.rotate_right(),
+            Coordinate::from(Direction::Right.delta())
+        );
+        assert_eq!(up.rotate_left(), Coordinate::from(Direction::Left.delta()));
+        assert_eq!(c.rotate(1), Coordinate::new(2, 3));
+        assert_eq!(c.rotate(2), Coordinate::new(-3, 2));
+        assert_eq!(c.rotate(-1), c.rotate(3));
+        assert_eq!(c.rotate(-1), c.rotate_left());
+        assert_eq!(c.rotate(4), c);
+        assert_eq!(c.rotate_right().rotate_left(), c);
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let c = Coordinate::new(1, 1);
+
+        assert_eq!(
+            c.neighbors4(),
+            [
+                Coordinate::new(1, 0),
+                Coordinate::new(2, 1),
+                Coordinate::new(1, 2),
+                Coordinate::new(0, 1)
+            ]
+        );
+
+        let neighbors = c.neighbors8();
+        assert_eq!(neighbors[1], Coordinate::new(2, 0));
+        assert_eq!(neighbors[7], Coordinate::new(0, 0));
+        assert!(neighbors.iter().all(|n| n.chebyshev_distance_to(&c) == 1));
+        assert!(c.neighbors4().iter().all(|n| neighbors.contains(n)));
+    }
+
+    #[test]
+    fn test_turn() {
+        let mut direction = Direction::Up;
+
+        for expected in &[
+            Direction::Right,
+            Direction::Down,
+            Direction::Left,
+            Direction::Up,
+        ] {
+            direction = direction.turn_right();
+            assert_eq!(direction, *expected);
+        }
+
+        assert_eq!(Direction::UpRight.turn_right(), Direction::DownRight);
+        assert_eq!(Direction::UpRight.turn_left(), Direction::UpLeft);
+        assert_eq!(
+            Direction::Vector(2, -1).turn_right(),
+            Direction::Vector(1, 2)
+        );
+        assert_eq!(
+            Direction::Vector(2, -1).turn_left().turn_right(),
+            Direction::Vector(2, -1)
+        );
+        assert_eq!(Direction::from_delta((0, 1)), Direction::Down);
+    }
+
+    #[test]
+    fn test_coordinate_order() {
+        let mut coordinates = vec![
+            Coordinate::new(1, 0),
+            Coordinate::new(0, 2),
+            Coordinate::new(0, -1),
+            Coordinate::new(2, 2),
+        ];
+
+        coordinates.sort();
+        assert_eq!(
+            coordinates,
+            vec![
+                Coordinate::new(0, -1),
+                Coordinate::new(0, 2),
+                Coordinate::new(1, 0),
+                Coordinate::new(2, 2)
+            ]
+        );
+
+        coordinates.sort_by_key(|c| ReadingOrder(c.clone()));
+        assert_eq!(
+            coordinates,
+            vec![
+                Coordinate::new(0, -1),
+                Coordinate::new(1, 0),
+                Coordinate::new(0, 2),
+                Coordinate::new(2, 2)
+            ]
+        );
+
+        // Same angle from the origin, but still two distinct members.
+        let set = vec![Coordinate::new(1, 1), Coordinate::new(2, 2)]
+            .into_iter()
+            .collect::<std::collections::BTreeSet<_>>();
+        assert_eq!(set.len(), 2);
+    }
+
+    #[test]
+    fn test_angle_from() {
+        let origin = Coordinate::new(5, 5);
+        let laser = AngleFrom(origin.clone());
+        let expected = vec![
+            Coordinate::new(5, 5),
+            Coordinate::new(5, 1),
+            Coordinate::new(5, 0),
+            Coordinate::new(6, 0),
+            Coordinate::new(7, 3),
+            Coordinate::new(9, 5),
+            Coordinate::new(9, 9),
+            Coordinate::new(5, 6),
+            Coordinate::new(2, 8),
+            Coordinate::new(0, 5),
+            Coordinate::new(4, 0),
+        ];
+
+        let mut coordinates = expected.clone();
+        coordinates.reverse();
+        coordinates.sort_by(|a, b| laser.compare(a, b));
+
+        assert_eq!(coordinates, expected);
+        assert_eq!(laser.angle(&Coordinate::new(9, 5)), 90.0);
+        assert_eq!(laser.angle(&Coordinate::new(0, 5)), 270.0);
+        assert_eq!(
+            laser.compare(&Coordinate::new(6, 6), &Coordinate::new(7, 7)),
+            Ordering::Less
+        );
+    }
+
+    #[test]
+    fn test_metric() {
+        let (a, b) = (Coordinate { x: 1, y: -2 }, Coordinate { x: 4, y: 2 });
+
+        assert_eq!(a.manhattan_distance_to(&b), 7);
+        assert_eq!(a.chebyshev_distance_to(&b), 4);
+        assert_eq!(a.squared_distance_to(&b), 25);
+        assert_eq!(a.distance_to(&b, Distance::Euclidean), 5.0);
+        assert_eq!(b.distance_to(&a, Distance::Manhattan), 7.0);
+        assert_eq!(b.distance_to(&a, Distance::Chebyshev), 4.0);
+    }
+
+    #[test]
+    fn test_shared_origin() {
+        let origin = Coordinate { x: 10, y: 10 };
+        let mut grid = Grid::with_origin(origin.clone());
+        grid.add_route(&route("R8,U5,L5,D3"));
+        grid.add_route(&route("U7,R6,D4,L4"));
+
+        assert_eq!(grid.start(1), &origin);
+        assert!(!grid.intersection().contains(&origin));
+        assert_eq!(
+            grid.closest_to_origin_in_intersection(),
+            Some(Coordinate { x: 13, y: 7 })
+        );
+        assert_eq!(grid.intersection_shortest_path(), 30);
+        assert_eq!(grid.pairwise_crossings()[0].distance, 6);
+    }
+
+    #[test]
+    fn test_closest_in_intersection() {
+        let mut grid = Grid::default();
+        grid.add_route(&route("R10"));
+        grid.add_route_at(&route("U5,R2,D7"), Coordinate { x: 4, y: 3 });
+
+        let closest =
+            |x, y, metric| grid.closest_in_intersection(&Coordinate { x, y }, metric);
+        let (near, far) = (Coordinate { x: 4, y: 0 }, Coordinate { x: 6, y: 0 });
+
+        assert_eq!(grid.steps_to_all(&near), Some(4 + 3));
+        assert_eq!(grid.steps_to_all(&far), Some(6 + 9));
+        assert_eq!(closest(0, 0, Metric::default()), Some(near.clone()));
+        assert_eq!(
+            closest(7, 0, Metric::Distance(Distance::Manhattan)),
+            Some(far.clone())
+        );
+        assert_eq!(
+            closest(6, -2, Metric::Distance(Distance::Euclidean)),
+            Some(far.clone())
+        );
+        assert_eq!(
+            closest(6, -2, Metric::Distance(Distance::Chebyshev)),
+            Some(near.clone())
+        );
+        assert_eq!(closest(9, 0, Metric::Steps), Some(near));
+    }
+
+    #[test]
+    fn test_start_on_another_wire() {
+        let mut grid = Grid::default();
+        grid.add_route(&route("R10"));
+        grid.add_route_at(&route("U2"), Coordinate { x: 6, y: 0 });
+
+        assert_eq!(
+            grid.crossings(0, 1),
+            vec![(Coordinate { x: 6, y: 0 }, (6, 0))]
+                .into_iter()
+                .collect()
+        );
+        assert_eq!(grid.intersection_shortest_path(), 6);
+    }
+
+    proptest! {
+        #[test]
+        fn test_segment_crossings_match_cells(
+            a in (-6isize..6, -6isize..6, -3isize..4, -3isize..4, 0usize..8),
+            b in (-6isize..6, -6isize..6, -3isize..4, -3isize..4, 0usize..8),
+        ) {
+            prop_assume!(a.2 != 0 || a.3 != 0);
+            prop_assume!(b.2 != 0 || b.3 != 0);
+
+            let a = segment(a.0, a.1, Direction::Vector(a.2, a.3), a.4);
+            let b = segment(b.0, b.1, Direction::Vector(b.2, b.3), b.4);
+
+            let mut crossings = a.crossings(&b);
+            crossings.sort();
+
+            prop_assert_eq!(crossings, naive_crossings(&a, &b));
+        }
+    }
 }
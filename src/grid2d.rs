@@ -0,0 +1,524 @@
+use crate::grid::Coordinate;
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+use std::ops::{Index, IndexMut};
+use std::str::FromStr;
+
+#[derive(Debug, PartialEq)]
+pub enum ParseGridErrorKind {
+    Empty,
+    RaggedRow { expected: usize, found: usize },
+    InvalidCell(char),
+}
+
+/**
+ * Where parsing a grid failed. Rows and columns count from 1, as an editor shows them.
+ */
+#[derive(Debug, PartialEq)]
+pub struct ParseGridError {
+    pub row: usize,
+    pub column: usize,
+    pub kind: ParseGridErrorKind,
+}
+
+impl Error for ParseGridError {}
+
+impl fmt::Display for ParseGridError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            ParseGridErrorKind::Empty => write!(f, "Grid is empty"),
+            ParseGridErrorKind::RaggedRow { expected, found } => write!(
+                f,
+                "row {}: expected {} cells, found {}",
+                self.row, expected, found
+            ),
+            ParseGridErrorKind::InvalidCell(ch) => write!(
+                f,
+                "row {}, column {}: unexpected {:?}",
+                self.row, self.column, ch
+            ),
+        }
+    }
+}
+
+/**
+ * A rectangle of cells, stored row by row, addressed by `Coordinate` with (0, 0) at
+ * the top left.
+ */
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Grid2D<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid2D<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self {
+        Grid2D {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    /**
+     * The grid's rows, or `None` if they aren't all the same length.
+     */
+    pub fn from_rows(rows: &[Vec<T>]) -> Option<Self> {
+        let width = rows.first().map_or(0, Vec::len);
+
+        if rows.iter().any(|row| row.len() != width) {
+            return None;
+        }
+
+        Some(Grid2D {
+            width,
+            height: rows.len(),
+            cells: rows.concat(),
+        })
+    }
+
+    pub fn to_rows(&self) -> Vec<Vec<T>> {
+        self.rows().map(<[T]>::to_vec).collect()
+    }
+}
+
+impl<T> Grid2D<T> {
+    /**
+     * Cells in reading order. Panics unless there are `width * height` of them.
+     */
+    pub fn from_cells(width: usize, height: usize, cells: Vec<T>) -> Self {
+        assert_eq!(
+            cells.len(),
+            width * height,
+            "a {}x{} grid needs {} cells",
+            width,
+            height,
+            width * height
+        );
+
+        Grid2D {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /**
+     * Reads one row per line, turning each character into a cell with `cell`. Blank
+     * lines around the grid and the indentation every row shares are ignored, so
+     * spaces elsewhere are cells. Rows must all be the same width.
+     */
+    pub fn parse_with(
+        text: &str,
+        cell: impl Fn(char) -> Option<T>,
+    ) -> Result<Self, ParseGridError> {
+        let lines = text.lines().collect::<Vec<_>>();
+        let is_blank = |line: &&str| line.trim().is_empty();
+
+        let (first, rows) = match (
+            lines.iter().position(|line| !is_blank(line)),
+            lines.iter().rposition(|line| !is_blank(line)),
+        ) {
+            (Some(first), Some(last)) => (first, &lines[first..=last]),
+            _ => {
+                return Err(ParseGridError {
+                    row: 1,
+                    column: 1,
+                    kind: ParseGridErrorKind::Empty,
+                })
+            }
+        };
+
+        let indent = rows
+            .iter()
+            .filter(|line| !is_blank(line))
+            .map(|line| line.chars().take_while(|ch| ch.is_whitespace()).count())
+            .min()
+            .unwrap_or(0);
+
+        let mut cells = vec![];
+        let mut width = None;
+        let mut height = 0;
+
+        for (y, line) in rows.iter().enumerate() {
+            let mut found = 0;
+
+            for (x, ch) in line.chars().skip(indent).enumerate() {
+                let value = cell(ch).ok_or(ParseGridError {
+                    row: first + y + 1,
+                    column: indent + x + 1,
+                    kind: ParseGridErrorKind::InvalidCell(ch),
+                })?;
+
+                cells.push(value);
+                found += 1;
+            }
+
+            let expected = *width.get_or_insert(found);
+
+            if found != expected {
+                return Err(ParseGridError {
+                    row: first + y + 1,
+                    column: indent + expected.min(found) + 1,
+                    kind: ParseGridErrorKind::RaggedRow { expected, found },
+                });
+            }
+
+            height += 1;
+        }
+
+        Ok(Grid2D {
+            width: width.unwrap_or(0),
+            height,
+            cells,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    fn offset(&self, c: &Coordinate) -> Option<usize> {
+        let x = usize::try_from(c.x).ok().filter(|x| *x < self.width)?;
+        let y = usize::try_from(c.y).ok().filter(|y| *y < self.height)?;
+
+        Some(y * self.width + x)
+    }
+
+    fn coordinate(&self, offset: usize) -> Coordinate {
+        Coordinate::new(
+            (offset % self.width) as isize,
+            (offset / self.width) as isize,
+        )
+    }
+
+    pub fn contains(&self, c: &Coordinate) -> bool {
+        self.offset(c).is_some()
+    }
+
+    pub fn get(&self, c: &Coordinate) -> Option<&T> {
+        self.offset(c).map(|offset| &self.cells[offset])
+    }
+
+    pub fn get_mut(&mut self, c: &Coordinate) -> Option<&mut T> {
+        self.offset(c).map(move |offset| &mut self.cells[offset])
+    }
+
+    /**
+     * Sets the cell at `c`, returning the old value, or `None` if `c` is outside.
+     */
+    pub fn set(&mut self, c: &Coordinate, value: T) -> Option<T> {
+        self.get_mut(c).map(|cell| std::mem::replace(cell, value))
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.cells.iter_mut()
+    }
+
+    /**
+     * Every cell with its coordinate, in reading order.
+     */
+    pub fn iter(&self) -> impl Iterator<Item = (Coordinate, &T)> {
+        self.cells
+            .iter()
+            .enumerate()
+            .map(move |(offset, value)| (self.coordinate(offset), value))
+    }
+
+    pub fn coordinates(&self) -> impl Iterator<Item = Coordinate> + '_ {
+        (0..self.cells.len()).map(move |offset| self.coordinate(offset))
+    }
+
+    pub fn row(&self, y: usize) -> Option<&[T]> {
+        if y < self.height {
+            Some(&self.cells[y * self.width..(y + 1) * self.width])
+        } else {
+            None
+        }
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.height).map(move |y| &self.cells[y * self.width..(y + 1) * self.width])
+    }
+
+    pub fn column(&self, x: usize) -> Option<impl Iterator<Item = &T>> {
+        if x < self.width {
+            Some(self.cells.iter().skip(x).step_by(self.width))
+        } else {
+            None
+        }
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(move |x| self.cells.iter().skip(x).step_by(self.width))
+    }
+
+    /**
+     * The orthogonal neighbours of `c` that are inside the grid, clockwise from up.
+     */
+    pub fn neighbors4<'a>(
+        &'a self,
+        c: &Coordinate,
+    ) -> impl Iterator<Item = (Coordinate, &'a T)> + 'a {
+        let neighbors = c.neighbors4();
+
+        IntoIterator::into_iter(neighbors).filter_map(move |n| {
+            let value = self.get(&n)?;
+            Some((n, value))
+        })
+    }
+
+    /**
+     * All neighbours of `c`, diagonals included, that are inside the grid.
+     */
+    pub fn neighbors8<'a>(
+        &'a self,
+        c: &Coordinate,
+    ) -> impl Iterator<Item = (Coordinate, &'a T)> + 'a {
+        let neighbors = c.neighbors8();
+
+        IntoIterator::into_iter(neighbors).filter_map(move |n| {
+            let value = self.get(&n)?;
+            Some((n, value))
+        })
+    }
+
+    pub fn map<U>(&self, f: impl Fn(&T) -> U) -> Grid2D<U> {
+        Grid2D {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+}
+
+impl<T> Index<&Coordinate> for Grid2D<T> {
+    type Output = T;
+
+    fn index(&self, c: &Coordinate) -> &T {
+        match self.offset(c) {
+            Some(offset) => &self.cells[offset],
+            None => panic!("{:?} is outside the {}x{} grid", c, self.width, self.height),
+        }
+    }
+}
+
+impl<T> IndexMut<&Coordinate> for Grid2D<T> {
+    fn index_mut(&mut self, c: &Coordinate) -> &mut T {
+        match self.offset(c) {
+            Some(offset) => &mut self.cells[offset],
+            None => panic!("{:?} is outside the {}x{} grid", c, self.width, self.height),
+        }
+    }
+}
+
+impl FromStr for Grid2D<char> {
+    type Err = ParseGridError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        Grid2D::parse_with(text, Some)
+    }
+}
+
+// One line per row, cells written side by side.
+impl<T: fmt::Display> fmt::Display for Grid2D<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn digits() -> Grid2D<u32> {
+        Grid2D::parse_with("123\n456\n", |ch| ch.to_digit(10)).unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let grid = digits();
+
+        assert_eq!((grid.width(), grid.height(), grid.len()), (3, 2, 6));
+        assert_eq!(grid.to_rows(), vec![vec![1, 2, 3], vec![4, 5, 6]]);
+        assert_eq!(grid.to_string(), "123\n456\n");
+
+        let chars: Grid2D<char> = "\n    #.\n    .#\n  ".parse().unwrap();
+        assert_eq!(chars.to_string(), "#.\n.#\n");
+    }
+
+    #[test]
+    fn test_parse_spaces() {
+        let chars: Grid2D<char> = "# \n #\n".parse().unwrap();
+
+        assert_eq!((chars.width(), chars.height()), (2, 2));
+        assert_eq!(chars[&Coordinate::new(1, 0)], ' ');
+        assert_eq!(chars[&Coordinate::new(0, 1)], ' ');
+        assert_eq!(chars.to_string(), "# \n #\n");
+
+        // Only the indentation every row shares is dropped.
+        let chars: Grid2D<char> = "\n      #\n    #  \n".parse().unwrap();
+        assert_eq!(
+            chars.to_rows(),
+            vec![vec![' ', ' ', '#'], vec!['#', ' ', ' ']]
+        );
+
+        assert_eq!(
+            "    ##\n   ##".parse::<Grid2D<char>>().unwrap_err().kind,
+            ParseGridErrorKind::RaggedRow {
+                expected: 3,
+                found: 2
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error =
+            |text: &str| Grid2D::parse_with(text, |ch| ch.to_digit(10)).unwrap_err();
+
+        assert_eq!(error(" \n").kind, ParseGridErrorKind::Empty);
+        assert_eq!(
+            error("123\n45\n"),
+            ParseGridError {
+                row: 2,
+                column: 3,
+                kind: ParseGridErrorKind::RaggedRow {
+                    expected: 3,
+                    found: 2
+                }
+            }
+        );
+        assert_eq!(
+            error("123\n4x6\n"),
+            ParseGridError {
+                row: 2,
+                column: 2,
+                kind: ParseGridErrorKind::InvalidCell('x')
+            }
+        );
+        assert_eq!(
+            error("123\n4x6\n").to_string(),
+            "row 2, column 2: unexpected 'x'"
+        );
+
+        // Positions count from the text as given, not the grid inside it.
+        assert_eq!(
+            error("\n\n    123\n    4x6\n").to_string(),
+            "row 4, column 6: unexpected 'x'"
+        );
+        assert_eq!(
+            error("\n  123\n  45\n"),
+            ParseGridError {
+                row: 3,
+                column: 5,
+                kind: ParseGridErrorKind::RaggedRow {
+                    expected: 3,
+                    found: 2
+                }
+            }
+        );
+    }
+
+    #[test]
+    fn test_indexing() {
+        let mut grid = digits();
+
+        assert_eq!(grid[&Coordinate::new(2, 1)], 6);
+        assert_eq!(grid.get(&Coordinate::new(3, 0)), None);
+        assert_eq!(grid.get(&Coordinate::new(0, -1)), None);
+        assert!(!grid.contains(&Coordinate::new(0, 2)));
+
+        grid[&Coordinate::new(0, 0)] = 9;
+        assert_eq!(grid.set(&Coordinate::new(1, 0), 8), Some(2));
+        assert_eq!(grid.set(&Coordinate::new(5, 5), 8), None);
+        assert_eq!(grid.to_string(), "983\n456\n");
+    }
+
+    #[test]
+    #[should_panic(expected = "outside the 3x2 grid")]
+    fn test_index_out_of_bounds() {
+        let _ = digits()[&Coordinate::new(-1, 0)];
+    }
+
+    #[test]
+    fn test_rows_and_columns() {
+        let grid = digits();
+
+        assert_eq!(grid.row(1), Some(&[4, 5, 6][..]));
+        assert_eq!(grid.row(2), None);
+        assert_eq!(
+            grid.column(1).unwrap().copied().collect::<Vec<_>>(),
+            vec![2, 5]
+        );
+        assert!(grid.column(3).is_none());
+        assert_eq!(
+            grid.columns()
+                .map(|column| column.sum::<u32>())
+                .collect::<Vec<_>>(),
+            vec![5, 7, 9]
+        );
+        assert_eq!(grid.iter().nth(4), Some((Coordinate::new(1, 1), &5)));
+        assert_eq!(grid.coordinates().last(), Some(Coordinate::new(2, 1)));
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let grid = digits();
+        let corner = Coordinate::new(0, 0);
+
+        assert_eq!(
+            grid.neighbors4(&corner)
+                .map(|(_, value)| *value)
+                .collect::<Vec<_>>(),
+            vec![2, 4]
+        );
+        assert_eq!(
+            grid.neighbors8(&corner)
+                .map(|(_, value)| *value)
+                .collect::<Vec<_>>(),
+            vec![2, 5, 4]
+        );
+        assert_eq!(grid.neighbors8(&Coordinate::new(1, 0)).count(), 5);
+    }
+
+    #[test]
+    fn test_from_rows_and_map() {
+        assert!(Grid2D::from_rows(&[vec![1, 2], vec![3]]).is_none());
+
+        let grid = Grid2D::from_rows(&[vec![1, 2], vec![3, 4]]).unwrap();
+
+        assert_eq!(grid, Grid2D::from_cells(2, 2, vec![1, 2, 3, 4]));
+        assert_eq!(
+            grid.map(|n| n % 2 == 0).to_rows(),
+            vec![vec![false, true], vec![false, true]]
+        );
+        assert_eq!(Grid2D::new(2, 1, '.').to_string(), "..\n");
+    }
+}
@@ -0,0 +1,241 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/**
+ * Environment variable naming a directory of personal inputs (`d01.txt`, `d02.txt`, ...).
+ */
+pub const INPUT_DIR_VAR: &str = "AOC_INPUT_DIR";
+
+#[derive(Debug)]
+pub enum InputError {
+    NotFound { day: u32, searched: Option<PathBuf> },
+    Empty { source: String },
+    Io { path: PathBuf, error: io::Error },
+}
+
+impl Error for InputError {}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputError::NotFound {
+                day,
+                searched: Some(path),
+            } => write!(
+                f,
+                "No input for day {}: {} does not exist and none is bundled",
+                day,
+                path.display()
+            ),
+            InputError::NotFound {
+                day,
+                searched: None,
+            } => write!(
+                f,
+                "No input for day {}: set {} to a directory containing {}",
+                day,
+                INPUT_DIR_VAR,
+                file_name(*day)
+            ),
+            InputError::Empty { source } => write!(f, "Input {} is empty", source),
+            InputError::Io { path, error } => {
+                write!(f, "Could not read {}: {}", path.display(), error)
+            }
+        }
+    }
+}
+
+pub fn file_name(day: u32) -> String {
+    format!("d{:02}.txt", day)
+}
+
+/**
+ * The inputs compiled into the crate from `data/`.
+ */
+pub fn bundled(day: u32) -> Option<&'static str> {
+    match day {
+        1 => Some(include_str!("../data/d01.txt")),
+        2 => Some(include_str!("../data/d02.txt")),
+        3 => Some(include_str!("../data/d03.txt")),
+        4 => Some(include_str!("../data/d04.txt")),
+        5 => Some(include_str!("../data/d05.txt")),
+        6 => Some(include_str!("../data/d06.txt")),
+        7 => Some(include_str!("../data/d07.txt")),
+        8 => Some(include_str!("../data/d08.txt")),
+        9 => Some(include_str!("../data/d09.txt")),
+        10 => Some(include_str!("../data/d10.txt")),
+        _ => None,
+    }
+}
+
+fn validate(text: String, source: impl fmt::Display) -> Result<String, InputError> {
+    if text.trim().is_empty() {
+        return Err(InputError::Empty {
+            source: source.to_string(),
+        });
+    }
+
+    Ok(text)
+}
+
+pub fn read_file(path: &Path) -> Result<String, InputError> {
+    let text = fs::read_to_string(path).map_err(|error| InputError::Io {
+        path: path.to_owned(),
+        error,
+    })?;
+
+    validate(text, path.display())
+}
+
+/**
+ * Finds each day's input, preferring the configured directory over the bundled
+ * files, and keeps what it has loaded.
+ */
+#[derive(Debug, Default)]
+pub struct Inputs {
+    dir: Option<PathBuf>,
+    cache: HashMap<u32, String>,
+}
+
+impl Inputs {
+    pub fn new(dir: Option<PathBuf>) -> Self {
+        Inputs {
+            dir,
+            ..Default::default()
+        }
+    }
+
+    pub fn from_env() -> Self {
+        Self::new(std::env::var_os(INPUT_DIR_VAR).map(PathBuf::from))
+    }
+
+    pub fn dir(&self) -> Option<&Path> {
+        self.dir.as_deref()
+    }
+
+    pub fn path(&self, day: u32) -> Option<PathBuf> {
+        self.dir.as_ref().map(|dir| dir.join(file_name(day)))
+    }
+
+    /**
+     * Whether `load` would read the bundled input for `day` rather than one from the
+     * directory.
+     */
+    pub fn is_bundled(&self, day: u32) -> bool {
+        !self.path(day).is_some_and(|path| path.exists()) && bundled(day).is_some()
+    }
+
+    pub fn load(&mut self, day: u32) -> Result<&str, InputError> {
+        if !self.cache.contains_key(&day) {
+            let text = self.resolve(day)?;
+            self.cache.insert(day, text);
+        }
+
+        Ok(&self.cache[&day])
+    }
+
+    fn resolve(&self, day: u32) -> Result<String, InputError> {
+        let path = self.path(day);
+
+        if let Some(path) = path.as_ref().filter(|path| path.exists()) {
+            return read_file(path);
+        }
+
+        match bundled(day) {
+            Some(text) => {
+                validate(text.to_string(), format!("bundled {}", file_name(day)))
+            }
+            None => Err(InputError::NotFound {
+                day,
+                searched: path,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "adventofcode2019rust-input-{}-{}",
+            name,
+            std::process::id()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_bundled() {
+        let mut inputs = Inputs::new(None);
+
+        assert!(inputs.load(1).unwrap().starts_with("50669"));
+        assert_eq!(inputs.load(4).unwrap().trim(), "273025-767253");
+
+        match inputs.load(25) {
+            Err(InputError::NotFound {
+                day: 25,
+                searched: None,
+            }) => (),
+            other => panic!("expected NotFound, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_dir_overrides_bundled() {
+        let dir = temp_dir("override");
+        fs::write(dir.join("d01.txt"), "12\n14\n").unwrap();
+
+        let mut inputs = Inputs::new(Some(dir.clone()));
+
+        assert_eq!(inputs.load(1).unwrap(), "12\n14\n");
+        assert!(inputs.load(2).unwrap().starts_with("1,0,0,3"));
+        assert!(!inputs.is_bundled(1));
+        assert!(inputs.is_bundled(2));
+        assert!(!inputs.is_bundled(25));
+
+        match inputs.load(25) {
+            Err(InputError::NotFound {
+                searched: Some(path),
+                ..
+            }) => assert_eq!(path, dir.join("d25.txt")),
+            other => panic!("expected NotFound, got {:?}", other),
+        }
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_empty_input() {
+        let dir = temp_dir("empty");
+        fs::write(dir.join("d03.txt"), " \n").unwrap();
+
+        let error = Inputs::new(Some(dir.clone())).load(3).unwrap_err();
+
+        assert!(matches!(error, InputError::Empty { .. }));
+        assert!(error.to_string().contains("d03.txt"));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_load_is_cached() {
+        let dir = temp_dir("cached");
+        let path = dir.join("d01.txt");
+        fs::write(&path, "12\n").unwrap();
+
+        let mut inputs = Inputs::new(Some(dir.clone()));
+        assert_eq!(inputs.load(1).unwrap(), "12\n");
+
+        fs::write(&path, "14\n").unwrap();
+        assert_eq!(inputs.load(1).unwrap(), "12\n");
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -1,10 +1,18 @@
+pub mod answers;
 pub mod asteroids;
 pub mod code;
 pub mod fuel;
 pub mod grid;
+pub mod grid2d;
+pub mod input;
 pub mod orbits;
+pub mod pathfinding;
 pub mod program;
+pub mod reference;
+pub mod render;
 pub mod sif;
+pub mod solution;
+pub mod transpiler;
 
 #[macro_use]
 extern crate lazy_static;
@@ -92,8 +100,10 @@ mod test {
     #[test]
     fn test_day_03() {
         let test_grid_1 = Grid::from(&vec![
-            Route::from("R75,D30,R83,U83,L12,D49,R71,U7,L72"),
-            Route::from("U62,R66,U55,R34,D71,R55,D58,R83"),
+            "R75,D30,R83,U83,L12,D49,R71,U7,L72"
+                .parse::<Route>()
+                .unwrap(),
+            "U62,R66,U55,R34,D71,R55,D58,R83".parse::<Route>().unwrap(),
         ]);
 
         let c1 = test_grid_1
@@ -102,8 +112,12 @@ mod test {
         assert_eq!(c1.manhattan_distance(), 159);
 
         let test_grid_2 = Grid::from(&vec![
-            Route::from("R98,U47,R26,D63,R33,U87,L62,D20,R33,U53,R51"),
-            Route::from("U98,R91,D20,R16,D67,R40,U7,R15,U6,R7"),
+            "R98,U47,R26,D63,R33,U87,L62,D20,R33,U53,R51"
+                .parse::<Route>()
+                .unwrap(),
+            "U98,R91,D20,R16,D67,R40,U7,R15,U6,R7"
+                .parse::<Route>()
+                .unwrap(),
         ]);
 
         let c2 = test_grid_2
@@ -115,7 +129,7 @@ mod test {
         let routes: Vec<Route> = include_str!("../data/d03.txt")
             .trim()
             .split_whitespace()
-            .map(Route::from)
+            .map(|line| line.parse().unwrap())
             .collect();
 
         let grid = Grid::from(&routes);
@@ -130,15 +144,21 @@ mod test {
     #[test]
     fn test_day_03_part_2() {
         let test_grid_1 = Grid::from(&vec![
-            Route::from("R75,D30,R83,U83,L12,D49,R71,U7,L72"),
-            Route::from("U62,R66,U55,R34,D71,R55,D58,R83"),
+            "R75,D30,R83,U83,L12,D49,R71,U7,L72"
+                .parse::<Route>()
+                .unwrap(),
+            "U62,R66,U55,R34,D71,R55,D58,R83".parse::<Route>().unwrap(),
         ]);
 
         assert_eq!(test_grid_1.intersection_shortest_path(), 610);
 
         let test_grid_2 = Grid::from(&vec![
-            Route::from("R98,U47,R26,D63,R33,U87,L62,D20,R33,U53,R51"),
-            Route::from("U98,R91,D20,R16,D67,R40,U7,R15,U6,R7"),
+            "R98,U47,R26,D63,R33,U87,L62,D20,R33,U53,R51"
+                .parse::<Route>()
+                .unwrap(),
+            "U98,R91,D20,R16,D67,R40,U7,R15,U6,R7"
+                .parse::<Route>()
+                .unwrap(),
         ]);
 
         assert_eq!(test_grid_2.intersection_shortest_path(), 410);
@@ -147,7 +167,7 @@ mod test {
         let routes: Vec<Route> = include_str!("../data/d03.txt")
             .trim()
             .split_whitespace()
-            .map(Route::from)
+            .map(|line| line.parse().unwrap())
             .collect();
 
         let grid = Grid::from(&routes);
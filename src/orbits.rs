@@ -1,8 +1,11 @@
+use crate::solution::{Answer, Solution};
 use petgraph::algo::astar;
 use petgraph::algo::bellman_ford;
 use petgraph::prelude::*;
 use rayon::prelude::*;
 use std::collections::HashSet;
+use std::error::Error;
+use std::fmt;
 
 #[derive(Debug)]
 pub struct Universe {
@@ -17,7 +20,53 @@ impl From<&str> for Universe {
     }
 }
 
+/**
+ * A word that isn't an orbit like `A)B`, with its 1-based line.
+ */
+#[derive(Debug, PartialEq)]
+pub struct ParseOrbitError {
+    pub line: usize,
+    pub text: String,
+}
+
+impl Error for ParseOrbitError {}
+
+impl fmt::Display for ParseOrbitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}: expected an orbit like A)B, found {:?}",
+            self.line, self.text
+        )
+    }
+}
+
 impl Universe {
+    /**
+     * Like `Universe::from`, but fails on anything that isn't an orbit.
+     */
+    pub fn parse(lines: &str) -> Result<Self, ParseOrbitError> {
+        let mut universe = Universe {
+            objects: HashSet::new(),
+            orbits: HashSet::new(),
+        };
+
+        for (i, line) in lines.lines().enumerate() {
+            for word in line.split_whitespace() {
+                let (objects, orbit) =
+                    Orbit::parse(word).ok_or_else(|| ParseOrbitError {
+                        line: i + 1,
+                        text: word.to_string(),
+                    })?;
+
+                universe.objects.extend(objects);
+                universe.orbits.insert(orbit);
+            }
+        }
+
+        Ok(universe)
+    }
+
     fn parse_orbits(lines: &str) -> ParsedOrbits {
         let mut objects = HashSet::new();
         let mut orbits = HashSet::new();
@@ -134,6 +183,20 @@ type ParsedOrbits = (HashSet<Object>, HashSet<Orbit>);
 type ParsedOrbit = (HashSet<Object>, Orbit);
 
 impl Orbit {
+    // Exactly two non-empty names either side of a `)`.
+    fn parse(s: &str) -> Option<ParsedOrbit> {
+        let mut names = s.split(')');
+
+        match (names.next(), names.next(), names.next()) {
+            (Some(orbited), Some(orbiting), None)
+                if !orbited.is_empty() && !orbiting.is_empty() =>
+            {
+                Some(Self::new(s))
+            }
+            _ => None,
+        }
+    }
+
     fn new(s: &str) -> ParsedOrbit {
         let split = s.split(')');
         let objects = split
@@ -148,6 +211,27 @@ impl Orbit {
     }
 }
 
+pub struct Day06;
+
+impl Solution for Day06 {
+    type Input = Universe;
+
+    fn parse(input: &str) -> Result<Self::Input, Box<dyn Error>> {
+        Ok(Universe::parse(input)?)
+    }
+
+    fn part1(universe: &Self::Input) -> Answer {
+        Ok(universe.count_indirect_orbits().to_string())
+    }
+
+    fn part2(universe: &Self::Input) -> Answer {
+        universe
+            .get_minimal_orbital_transfer_count("YOU", "SAN")
+            .map(|count| count.to_string())
+            .ok_or_else(|| "No transfer path from YOU to SAN".into())
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -167,6 +251,26 @@ mod test {
         assert_eq!(Orbit::new("A)B"), (objects, orbit));
     }
 
+    #[test]
+    fn test_universe_parse() {
+        let universe = Universe::parse("COM)A\nA)B  B)C\n").unwrap();
+        assert_eq!(universe.count_indirect_orbits(), 6);
+
+        assert_eq!(
+            Universe::parse("COM)A\n\nCOM-B").unwrap_err(),
+            ParseOrbitError {
+                line: 3,
+                text: "COM-B".to_string()
+            }
+        );
+        assert_eq!(Universe::parse("A)B)C").unwrap_err().line, 1);
+        assert_eq!(Universe::parse("A)").unwrap_err().line, 1);
+        assert_eq!(
+            Day06::parse("COM)A\nB").unwrap_err().to_string(),
+            "line 2: expected an orbit like A)B, found \"B\""
+        );
+    }
+
     #[test]
     fn test_universe_from() {
         let universe = Universe::from("COM)A  A)B  B)C");
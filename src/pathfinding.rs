@@ -0,0 +1,393 @@
+use crate::grid::Coordinate;
+use crate::grid2d::Grid2D;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+
+/**
+ * A map that can be walked one cell at a time.
+ */
+pub trait Walkable {
+    fn is_open(&self, c: &Coordinate) -> bool;
+
+    /**
+     * What it costs to step onto `c`. Costs below 1 make `astar` unreliable.
+     */
+    fn cost(&self, _c: &Coordinate) -> usize {
+        1
+    }
+
+    /**
+     * The open cells one step from `c`. Orthogonal steps unless overridden.
+     */
+    fn neighbors(&self, c: &Coordinate) -> Vec<Coordinate> {
+        c.neighbors4()
+            .iter()
+            .filter(|n| self.is_open(n))
+            .cloned()
+            .collect()
+    }
+}
+
+// Open cells are `true`.
+impl Walkable for Grid2D<bool> {
+    fn is_open(&self, c: &Coordinate) -> bool {
+        self.get(c) == Some(&true)
+    }
+}
+
+// Walls are `None`; open cells hold the cost of stepping onto them.
+impl Walkable for Grid2D<Option<usize>> {
+    fn is_open(&self, c: &Coordinate) -> bool {
+        matches!(self.get(c), Some(Some(_)))
+    }
+
+    fn cost(&self, c: &Coordinate) -> usize {
+        self.get(c).copied().flatten().unwrap_or(0)
+    }
+}
+
+// The set holds the open cells.
+impl Walkable for HashSet<Coordinate> {
+    fn is_open(&self, c: &Coordinate) -> bool {
+        self.contains(c)
+    }
+}
+
+/**
+ * The cells from start to goal, both included, and what it cost to walk them.
+ */
+#[derive(Clone, Debug, PartialEq)]
+pub struct ShortestPath {
+    pub cost: usize,
+    pub path: Vec<Coordinate>,
+}
+
+impl ShortestPath {
+    pub fn start(&self) -> &Coordinate {
+        &self.path[0]
+    }
+
+    pub fn goal(&self) -> &Coordinate {
+        &self.path[self.path.len() - 1]
+    }
+}
+
+fn trace(
+    came_from: &HashMap<Coordinate, Coordinate>,
+    goal: Coordinate,
+) -> Vec<Coordinate> {
+    let mut path = vec![goal];
+
+    while let Some(previous) = came_from.get(&path[path.len() - 1]) {
+        path.push(previous.clone());
+    }
+
+    path.reverse();
+    path
+}
+
+/**
+ * The fewest steps from any of `starts` to a cell where `is_goal` holds, ignoring costs.
+ */
+pub fn bfs_from(
+    map: &impl Walkable,
+    starts: &[Coordinate],
+    is_goal: impl Fn(&Coordinate) -> bool,
+) -> Option<ShortestPath> {
+    let mut seen = starts.iter().cloned().collect::<HashSet<_>>();
+    let mut came_from = HashMap::new();
+    let mut queue = starts
+        .iter()
+        .map(|c| (c.clone(), 0))
+        .collect::<VecDeque<_>>();
+
+    while let Some((c, steps)) = queue.pop_front() {
+        if is_goal(&c) {
+            return Some(ShortestPath {
+                cost: steps,
+                path: trace(&came_from, c),
+            });
+        }
+
+        for next in map.neighbors(&c) {
+            if seen.insert(next.clone()) {
+                came_from.insert(next.clone(), c.clone());
+                queue.push_back((next, steps + 1));
+            }
+        }
+    }
+
+    None
+}
+
+pub fn bfs(
+    map: &impl Walkable,
+    start: &Coordinate,
+    goal: &Coordinate,
+) -> Option<ShortestPath> {
+    bfs_from(map, std::slice::from_ref(start), |c| c == goal)
+}
+
+// Dijkstra when `heuristic` is always 0, A* otherwise. The heap holds indexes into
+// `cells` so that ties break by insertion order rather than by `Coordinate`.
+fn search(
+    map: &impl Walkable,
+    starts: &[Coordinate],
+    is_goal: impl Fn(&Coordinate) -> bool,
+    heuristic: impl Fn(&Coordinate) -> usize,
+) -> Option<ShortestPath> {
+    let mut cells = vec![];
+    let mut best = HashMap::new();
+    let mut came_from = HashMap::new();
+    let mut heap = BinaryHeap::new();
+
+    for start in starts {
+        best.insert(start.clone(), 0);
+        heap.push(Reverse((heuristic(start), 0, cells.len())));
+        cells.push(start.clone());
+    }
+
+    while let Some(Reverse((_, cost, index))) = heap.pop() {
+        let c = cells[index].clone();
+
+        if best.get(&c).is_some_and(|best| cost > *best) {
+            continue;
+        }
+
+        if is_goal(&c) {
+            return Some(ShortestPath {
+                cost,
+                path: trace(&came_from, c),
+            });
+        }
+
+        for next in map.neighbors(&c) {
+            let next_cost = cost + map.cost(&next);
+
+            if best.get(&next).is_none_or(|best| next_cost < *best) {
+                best.insert(next.clone(), next_cost);
+                came_from.insert(next.clone(), c.clone());
+                heap.push(Reverse((
+                    next_cost + heuristic(&next),
+                    next_cost,
+                    cells.len(),
+                )));
+                cells.push(next);
+            }
+        }
+    }
+
+    None
+}
+
+/**
+ * The cheapest walk from any of `starts` to a cell where `is_goal` holds.
+ */
+pub fn dijkstra_from(
+    map: &impl Walkable,
+    starts: &[Coordinate],
+    is_goal: impl Fn(&Coordinate) -> bool,
+) -> Option<ShortestPath> {
+    search(map, starts, is_goal, |_| 0)
+}
+
+pub fn dijkstra(
+    map: &impl Walkable,
+    start: &Coordinate,
+    goal: &Coordinate,
+) -> Option<ShortestPath> {
+    dijkstra_from(map, std::slice::from_ref(start), |c| c == goal)
+}
+
+/**
+ * Like `dijkstra`, guided towards `goal` by the Manhattan distance left to walk.
+ */
+pub fn astar(
+    map: &impl Walkable,
+    start: &Coordinate,
+    goal: &Coordinate,
+) -> Option<ShortestPath> {
+    search(
+        map,
+        std::slice::from_ref(start),
+        |c| c == goal,
+        |c| (goal - c).manhattan_distance(),
+    )
+}
+
+/**
+ * The fewest steps from the nearest of `starts` to every cell it can reach.
+ */
+pub fn flood_fill_from(
+    map: &impl Walkable,
+    starts: &[Coordinate],
+) -> HashMap<Coordinate, usize> {
+    let mut distances = starts
+        .iter()
+        .map(|c| (c.clone(), 0))
+        .collect::<HashMap<_, _>>();
+    let mut queue = starts.iter().cloned().collect::<VecDeque<_>>();
+
+    while let Some(c) = queue.pop_front() {
+        let steps = distances[&c];
+
+        for next in map.neighbors(&c) {
+            if !distances.contains_key(&next) {
+                distances.insert(next.clone(), steps + 1);
+                queue.push_back(next);
+            }
+        }
+    }
+
+    distances
+}
+
+pub fn flood_fill(map: &impl Walkable, start: &Coordinate) -> HashMap<Coordinate, usize> {
+    flood_fill_from(map, std::slice::from_ref(start))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const MAZE: &str = "
+        #########
+        #S..#...#
+        #.#.#.#.#
+        #.#...#G#
+        #########";
+
+    fn maze() -> Grid2D<bool> {
+        Grid2D::parse_with(MAZE, |ch| match ch {
+            '#' => Some(false),
+            '.' | 'S' | 'G' => Some(true),
+            _ => None,
+        })
+        .unwrap()
+    }
+
+    fn costs(text: &str) -> Grid2D<Option<usize>> {
+        Grid2D::parse_with(text, |ch| match ch {
+            '#' => Some(None),
+            _ => ch.to_digit(10).map(|cost| Some(cost as usize)),
+        })
+        .unwrap()
+    }
+
+    fn is_walk(map: &impl Walkable, path: &[Coordinate]) -> bool {
+        path.windows(2)
+            .all(|pair| map.neighbors(&pair[0]).contains(&pair[1]))
+    }
+
+    #[test]
+    fn test_bfs() {
+        let (maze, start, goal) = (maze(), Coordinate::new(1, 1), Coordinate::new(7, 3));
+
+        let found = bfs(&maze, &start, &goal).unwrap();
+
+        assert_eq!(found.cost, 12);
+        assert_eq!(found.path.len(), 13);
+        assert_eq!((found.start(), found.goal()), (&start, &goal));
+        assert!(is_walk(&maze, &found.path));
+
+        assert_eq!(
+            bfs(&maze, &start, &start).unwrap().path,
+            vec![start.clone()]
+        );
+        assert_eq!(bfs(&maze, &start, &Coordinate::new(0, 0)), None);
+    }
+
+    #[test]
+    fn test_dijkstra_and_astar() {
+        let map = costs("116\n138\n213");
+        let (start, goal) = (Coordinate::new(0, 0), Coordinate::new(2, 2));
+
+        let cheapest = dijkstra(&map, &start, &goal).unwrap();
+
+        assert_eq!(cheapest.cost, 7);
+        assert_eq!(
+            cheapest.path,
+            vec![
+                Coordinate::new(0, 0),
+                Coordinate::new(0, 1),
+                Coordinate::new(0, 2),
+                Coordinate::new(1, 2),
+                Coordinate::new(2, 2)
+            ]
+        );
+        assert_eq!(astar(&map, &start, &goal), Some(cheapest));
+        assert_eq!(bfs(&map, &start, &goal).unwrap().cost, 4);
+
+        let walled = costs("1#1\n1#1\n111");
+        assert_eq!(
+            dijkstra(&walled, &start, &Coordinate::new(2, 0))
+                .unwrap()
+                .cost,
+            6
+        );
+        assert_eq!(astar(&walled, &start, &Coordinate::new(1, 0)), None);
+    }
+
+    #[test]
+    fn test_astar_matches_dijkstra() {
+        let map = costs(
+            "
+            131673
+            919#11
+            8#1219
+            11#131
+            745231",
+        );
+        let start = Coordinate::new(0, 0);
+
+        for goal in map.coordinates().filter(|c| map.is_open(c)) {
+            assert_eq!(
+                astar(&map, &start, &goal).map(|found| found.cost),
+                dijkstra(&map, &start, &goal).map(|found| found.cost),
+                "{:?}",
+                goal
+            );
+        }
+    }
+
+    #[test]
+    fn test_flood_fill() {
+        let maze = maze();
+        let distances = flood_fill(&maze, &Coordinate::new(1, 1));
+
+        assert_eq!(distances.len(), 15);
+        assert_eq!(distances[&Coordinate::new(7, 3)], 12);
+        assert_eq!(distances.values().max(), Some(&12));
+    }
+
+    #[test]
+    fn test_multiple_sources() {
+        let maze = maze();
+        let sources = [Coordinate::new(1, 1), Coordinate::new(7, 3)];
+
+        let distances = flood_fill_from(&maze, &sources);
+        assert_eq!(distances[&Coordinate::new(5, 3)], 6);
+        assert_eq!(distances[&Coordinate::new(4, 3)], 5);
+        assert_eq!(distances[&Coordinate::new(7, 1)], 2);
+
+        let nearest = bfs_from(&maze, &sources, |c| *c == Coordinate::new(1, 3)).unwrap();
+        assert_eq!((nearest.cost, nearest.start()), (2, &sources[0]));
+
+        let nearest = dijkstra_from(&maze, &sources, |c| c.x == 5).unwrap();
+        assert_eq!(nearest.goal(), &Coordinate::new(5, 1));
+        assert_eq!(nearest.cost, 4);
+    }
+
+    #[test]
+    fn test_open_set() {
+        let open = (0..5)
+            .map(|x| Coordinate::new(x, 0))
+            .chain((0..5).map(|y| Coordinate::new(4, y)))
+            .collect::<HashSet<_>>();
+
+        let found = bfs(&open, &Coordinate::new(0, 0), &Coordinate::new(4, 4)).unwrap();
+
+        assert_eq!(found.cost, 8);
+        assert!(is_walk(&open, &found.path));
+    }
+}
@@ -1,18 +1,21 @@
 use crate::code::Digits;
+use crate::solution::{Answer, Solution};
 use itertools::Itertools;
 use num::cast::ToPrimitive;
 use num::Integer;
 use rayon::prelude::*;
 use std::collections::HashMap;
 use std::convert::TryInto;
+use std::error::Error;
+use std::num::ParseIntError;
 use std::sync::mpsc::{channel, Receiver, SendError, Sender, TryRecvError};
 
-const POSITION_MODE: u32 = 0;
-const IMMEDIATE_MODE: u32 = 1;
-const RELATIVE_MODE: u32 = 2;
+pub(crate) const POSITION_MODE: u32 = 0;
+pub(crate) const IMMEDIATE_MODE: u32 = 1;
+pub(crate) const RELATIVE_MODE: u32 = 2;
 
 lazy_static! {
-    static ref OPCODE_LENGTHS: HashMap<i64, usize> = {
+    pub(crate) static ref OPCODE_LENGTHS: HashMap<i64, usize> = {
         let mut map = HashMap::new();
         map.insert(1, 4);
         map.insert(2, 4);
@@ -74,7 +77,7 @@ impl From<&[i64]> for Program {
 }
 
 impl Program {
-    fn new(code: &[i64], inputs: &[i64]) -> Program {
+    pub fn new(code: &[i64], inputs: &[i64]) -> Program {
         let (sender, receiver) = channel();
 
         for input in inputs {
@@ -89,6 +92,24 @@ impl Program {
         }
     }
 
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    pub fn relative_base(&self) -> isize {
+        self.relative_base
+    }
+
+    // The words making up the instruction about to run.
+    pub fn next_instruction(&self) -> Vec<i64> {
+        let opcode = self.get(self.pos).unwrap();
+        let length = OPCODE_LENGTHS.get(&(opcode % 100)).cloned().unwrap_or(1);
+
+        (self.pos..self.pos + length)
+            .map(|pos| self.get(pos).unwrap())
+            .collect()
+    }
+
     pub fn finish(&mut self) {
         self.finished = true;
     }
@@ -211,29 +232,39 @@ impl Program {
     }
 
     pub fn run(&mut self) {
-        let mut opcode: Opcode;
+        while self.step() {
+            // println!("program: {:?}", self.code);
+            // println!();
+        }
+    }
 
-        loop {
-            let pos = self.pos;
-            let opcode_value = self.get(pos).unwrap();
-            opcode = Opcode::from(opcode_value);
+    // Runs a single instruction. Returns false once the program halts or stops to
+    // wait for input.
+    pub fn step(&mut self) -> bool {
+        let pos = self.pos;
+        let opcode_value = self.get(pos).unwrap();
+        let opcode = Opcode::from(opcode_value);
 
-            let mut instruction = Instruction::new(self, opcode);
-            instruction.init(pos);
+        let mut instruction = Instruction::new(self, opcode);
+        instruction.init(pos);
 
-            match instruction.run() {
-                Some(new_pos) => {
-                    self.pos = new_pos;
-                }
-                None => break,
+        match instruction.run() {
+            Some(new_pos) => {
+                self.pos = new_pos;
+                true
             }
-
-            // println!("program: {:?}", self.code);
-            // println!();
+            None => false,
         }
     }
 }
 
+pub fn parse_program(text: &str) -> Result<Vec<i64>, ParseIntError> {
+    text.trim()
+        .split(',')
+        .map(|node| node.trim().parse::<i64>())
+        .collect()
+}
+
 pub fn compose_program_with_noun_and_verb(
     original: &[i64],
     noun: i64,
@@ -279,6 +310,29 @@ pub fn run_program_with_inputs(original: &[i64], inputs: &[i64]) -> Program {
     program
 }
 
+// Feeds inputs one at a time, only when the program stops to wait for one.
+pub fn run_program_with_input_iter(
+    original: &[i64],
+    inputs: &mut impl Iterator<Item = i64>,
+) -> Program {
+    let mut program = Program::from(original);
+
+    loop {
+        program.run();
+
+        if program.is_finished() {
+            break;
+        }
+
+        match inputs.next() {
+            Some(input) => program.send_input(input).unwrap(),
+            None => break,
+        }
+    }
+
+    program
+}
+
 #[derive(Debug, Default)]
 struct Opcode {
     number: i64,
@@ -516,6 +570,102 @@ impl<'a> Instruction<'a> {
     }
 }
 
+fn diagnostic(code: &[i64], input: i64) -> Answer {
+    run_program_with_input(code, input)
+        .output()
+        .map(|output| output.to_string())
+        .ok_or_else(|| "Program produced no output".into())
+}
+
+pub struct Day02;
+
+impl Solution for Day02 {
+    type Input = Vec<i64>;
+
+    // Both parts write the noun and verb to addresses 1 and 2.
+    fn parse(input: &str) -> Result<Self::Input, Box<dyn Error>> {
+        let code = parse_program(input)?;
+
+        if code.len() < 3 {
+            return Err(format!(
+                "Expected at least 3 words for a noun and verb, found {}",
+                code.len()
+            )
+            .into());
+        }
+
+        Ok(code)
+    }
+
+    fn part1(code: &Self::Input) -> Answer {
+        let composed = compose_program_with_noun_and_verb(code, 12, 2);
+        Ok(run_program_and_get_output(&composed).to_string())
+    }
+
+    fn part2(code: &Self::Input) -> Answer {
+        run_program_to_get_output(code, 19_690_720)
+            .map(|(noun, verb)| (100 * noun + verb).to_string())
+            .ok_or_else(|| "No noun and verb produce 19690720".into())
+    }
+}
+
+pub struct Day05;
+
+impl Solution for Day05 {
+    type Input = Vec<i64>;
+
+    fn parse(input: &str) -> Result<Self::Input, Box<dyn Error>> {
+        Ok(parse_program(input)?)
+    }
+
+    fn part1(code: &Self::Input) -> Answer {
+        diagnostic(code, 1)
+    }
+
+    fn part2(code: &Self::Input) -> Answer {
+        diagnostic(code, 5)
+    }
+}
+
+pub struct Day07;
+
+impl Solution for Day07 {
+    type Input = Program;
+
+    fn parse(input: &str) -> Result<Self::Input, Box<dyn Error>> {
+        Ok(Program::from(parse_program(input)?.as_slice()))
+    }
+
+    fn part1(program: &Self::Input) -> Answer {
+        Ok(program.find_best_phase_settings(5).1.to_string())
+    }
+
+    fn part2(program: &Self::Input) -> Answer {
+        Ok(program
+            .find_best_phase_settings_in_feedback_loop_mode(5)
+            .1
+            .to_string())
+    }
+}
+
+pub struct Day09;
+
+impl Solution for Day09 {
+    type Input = Vec<i64>;
+
+    fn parse(input: &str) -> Result<Self::Input, Box<dyn Error>> {
+        Ok(parse_program(input)?)
+    }
+
+    fn part1(code: &Self::Input) -> Answer {
+        diagnostic(code, 1)
+    }
+
+    fn part2(code: &Self::Input) -> Answer {
+        diagnostic(code, 2)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -643,6 +793,53 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_parse_program() {
+        assert_eq!(parse_program("1,0, 0,3,99\n"), Ok(vec![1, 0, 0, 3, 99]));
+        assert!(parse_program("1,x").is_err());
+    }
+
+    #[test]
+    fn test_day02_needs_noun_and_verb() {
+        assert!(Day02::parse("99").is_err());
+        assert!(Day02::parse("1,0").is_err());
+        assert_eq!(Day02::parse("1,0,0").unwrap(), vec![1, 0, 0]);
+    }
+
+    #[test]
+    fn test_next_instruction() {
+        let mut prog = Program::from(&[1101, 2, 3, 7, 104, 7, 99, 0][..]);
+
+        assert_eq!(prog.next_instruction(), vec![1101, 2, 3, 7]);
+        prog.step();
+        assert_eq!(prog.position(), 4);
+        assert_eq!(prog.next_instruction(), vec![104, 7]);
+    }
+
+    #[test]
+    fn test_step() {
+        let mut prog = Program::new(&[1101, 2, 3, 5, 4, 0, 99], &[]);
+
+        assert!(prog.step());
+        assert_eq!(prog.code[5], 5);
+        assert!(prog.step());
+        assert_eq!(prog.all_output(), &[5]);
+        assert!(!prog.step());
+        assert!(prog.is_finished());
+    }
+
+    #[test]
+    fn test_run_program_with_input_iter() {
+        let program = vec![3, 9, 8, 9, 10, 9, 4, 9, 99, -1, 8];
+        let mut inputs = vec![8, 5].into_iter();
+
+        let prog = run_program_with_input_iter(&program, &mut inputs);
+
+        assert_eq!(prog.all_output(), &[1]);
+        assert!(prog.is_finished());
+        assert_eq!(inputs.next(), Some(5));
+    }
+
     #[test]
     fn test_phase_settings() {
         let program: &[i64] = &[
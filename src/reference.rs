@@ -0,0 +1,633 @@
+/**
+ * A slow but obviously correct Intcode interpreter, and a harness that runs it
+ * side by side with `program::Program` to catch the two disagreeing.
+ *
+ * Anything the puzzle text doesn't define (unknown opcodes or modes, writes in
+ * immediate mode, negative addresses, overflow) is a `Fault` here rather than
+ * something to work around.
+ */
+use crate::program::Program;
+use std::collections::VecDeque;
+use std::error::Error;
+use std::panic::{self, AssertUnwindSafe};
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Fault {
+    InvalidOpcode { pos: usize, value: i64 },
+    InvalidMode { pos: usize, value: i64 },
+    ImmediateWrite { pos: usize },
+    NegativeAddress { pos: usize, address: i64 },
+    Overflow { pos: usize },
+}
+
+impl Error for Fault {}
+
+impl std::fmt::Display for Fault {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Fault::InvalidOpcode { pos, value } => {
+                write!(f, "Invalid opcode {} at position {}", value, pos)
+            }
+            Fault::InvalidMode { pos, value } => {
+                write!(f, "Invalid parameter mode in {} at position {}", value, pos)
+            }
+            Fault::ImmediateWrite { pos } => {
+                write!(f, "Write in immediate mode at position {}", pos)
+            }
+            Fault::NegativeAddress { pos, address } => {
+                write!(f, "Negative address {} at position {}", address, pos)
+            }
+            Fault::Overflow { pos } => write!(f, "Overflow at position {}", pos),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum State {
+    Running,
+    AwaitingInput,
+    Halted,
+    Faulted(Fault),
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Machine {
+    pub memory: Vec<i64>,
+    pub output: Vec<i64>,
+    inputs: VecDeque<i64>,
+    pos: usize,
+    relative_base: i64,
+}
+
+impl Machine {
+    pub fn new(code: &[i64], inputs: &[i64]) -> Self {
+        Machine {
+            memory: code.to_vec(),
+            inputs: inputs.iter().cloned().collect(),
+            ..Default::default()
+        }
+    }
+
+    fn load(&self, address: usize) -> i64 {
+        if address < self.memory.len() {
+            self.memory[address]
+        } else {
+            0
+        }
+    }
+
+    fn store(&mut self, address: usize, value: i64) {
+        if address >= self.memory.len() {
+            self.memory.resize(address + 1, 0);
+        }
+        self.memory[address] = value;
+    }
+
+    fn mode(&self, n: usize) -> Result<i64, Fault> {
+        let value = self.load(self.pos);
+        let mode = value / 10_i64.pow(n as u32 + 1) % 10;
+
+        match mode {
+            0..=2 => Ok(mode),
+            _ => Err(Fault::InvalidMode {
+                pos: self.pos,
+                value,
+            }),
+        }
+    }
+
+    // Where parameter `n` (counting from 1) points, for position and relative mode.
+    fn address(&self, n: usize) -> Result<Option<usize>, Fault> {
+        let raw = self.load(self.pos + n);
+
+        let address = match self.mode(n)? {
+            0 => raw,
+            2 => self
+                .relative_base
+                .checked_add(raw)
+                .ok_or(Fault::Overflow { pos: self.pos })?,
+            _ => return Ok(None),
+        };
+
+        if address < 0 {
+            return Err(Fault::NegativeAddress {
+                pos: self.pos,
+                address,
+            });
+        }
+
+        Ok(Some(address as usize))
+    }
+
+    fn read(&self, n: usize) -> Result<i64, Fault> {
+        match self.address(n)? {
+            Some(address) => Ok(self.load(address)),
+            None => Ok(self.load(self.pos + n)),
+        }
+    }
+
+    fn write(&mut self, n: usize, value: i64) -> Result<(), Fault> {
+        match self.address(n)? {
+            Some(address) => {
+                self.store(address, value);
+                Ok(())
+            }
+            None => Err(Fault::ImmediateWrite { pos: self.pos }),
+        }
+    }
+
+    fn jump(&self, target: i64) -> Result<usize, Fault> {
+        if target < 0 {
+            return Err(Fault::NegativeAddress {
+                pos: self.pos,
+                address: target,
+            });
+        }
+
+        Ok(target as usize)
+    }
+
+    fn execute(&mut self) -> Result<State, Fault> {
+        let pos = self.pos;
+        let value = self.load(pos);
+
+        if value < 0 {
+            return Err(Fault::InvalidOpcode { pos, value });
+        }
+
+        let (opcode, length) = match value % 100 {
+            99 => (99, 1),
+            opcode @ 3 | opcode @ 4 | opcode @ 9 => (opcode, 2),
+            opcode @ 5 | opcode @ 6 => (opcode, 3),
+            opcode @ 1 | opcode @ 2 | opcode @ 7 | opcode @ 8 => (opcode, 4),
+            _ => return Err(Fault::InvalidOpcode { pos, value }),
+        };
+
+        // Every mode digit beyond the parameters must be zero, and every parameter
+        // has to make sense whether or not this instruction ends up using it.
+        if value / 10_i64.pow(length as u32 + 1) != 0 {
+            return Err(Fault::InvalidMode { pos, value });
+        }
+        for n in 1..length {
+            self.address(n)?;
+        }
+
+        let overflow = Fault::Overflow { pos };
+        let mut next = pos + length;
+
+        match opcode {
+            1 => {
+                let sum = self.read(1)?.checked_add(self.read(2)?).ok_or(overflow)?;
+                self.write(3, sum)?;
+            }
+            2 => {
+                let product = self.read(1)?.checked_mul(self.read(2)?).ok_or(overflow)?;
+                self.write(3, product)?;
+            }
+            3 => match self.inputs.pop_front() {
+                Some(input) => self.write(1, input)?,
+                None => return Ok(State::AwaitingInput),
+            },
+            4 => {
+                let out = self.read(1)?;
+                self.output.push(out);
+            }
+            5 => {
+                if self.read(1)? != 0 {
+                    next = self.jump(self.read(2)?)?;
+                }
+            }
+            6 => {
+                if self.read(1)? == 0 {
+                    next = self.jump(self.read(2)?)?;
+                }
+            }
+            7 => {
+                let less = self.read(1)? < self.read(2)?;
+                self.write(3, if less { 1 } else { 0 })?;
+            }
+            8 => {
+                let equal = self.read(1)? == self.read(2)?;
+                self.write(3, if equal { 1 } else { 0 })?;
+            }
+            9 => {
+                let adjust_by = self.read(1)?;
+                self.relative_base =
+                    self.relative_base.checked_add(adjust_by).ok_or(overflow)?;
+            }
+            _ => return Ok(State::Halted),
+        }
+
+        self.pos = next;
+
+        Ok(State::Running)
+    }
+
+    // Runs a single instruction. Waiting for input and halting leave the machine
+    // where it is, so it can be resumed.
+    pub fn step(&mut self) -> State {
+        match self.execute() {
+            Ok(state) => state,
+            Err(fault) => State::Faulted(fault),
+        }
+    }
+
+    pub fn send_input(&mut self, input: i64) {
+        self.inputs.push_back(input);
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Outcome {
+    Halted,
+    AwaitingInput,
+    Faulted,
+    StepLimit,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Snapshot {
+    pub outcome: Outcome,
+    pub memory: Vec<i64>,
+    pub output: Vec<i64>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Divergence {
+    pub reference: Snapshot,
+    pub program: Snapshot,
+    pub fault: Option<Fault>,
+}
+
+impl Error for Divergence {}
+
+impl std::fmt::Display for Divergence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Interpreters disagree: reference {:?}, program {:?}",
+            self.reference.outcome, self.program.outcome
+        )?;
+
+        if let Some(fault) = &self.fault {
+            write!(f, " ({})", fault)?;
+        }
+        if self.reference.output != self.program.output {
+            write!(
+                f,
+                "; output {:?} vs {:?}",
+                self.reference.output, self.program.output
+            )?;
+        }
+        if self.reference.memory != self.program.memory {
+            write!(f, "; memory differs")?;
+        }
+
+        Ok(())
+    }
+}
+
+pub fn run_reference(
+    code: &[i64],
+    inputs: &[i64],
+    limit: usize,
+) -> (Snapshot, Option<Fault>) {
+    let mut machine = Machine::new(code, inputs);
+    let mut outcome = (Outcome::StepLimit, None);
+
+    for _ in 0..limit {
+        match machine.step() {
+            State::Running => continue,
+            State::AwaitingInput => outcome = (Outcome::AwaitingInput, None),
+            State::Halted => outcome = (Outcome::Halted, None),
+            State::Faulted(fault) => outcome = (Outcome::Faulted, Some(fault)),
+        }
+        break;
+    }
+
+    let snapshot = Snapshot {
+        outcome: outcome.0,
+        memory: machine.memory,
+        output: machine.output,
+    };
+
+    (snapshot, outcome.1)
+}
+
+// `Program` panics on anything it can't run, which counts as a fault.
+pub fn run_program(code: &[i64], inputs: &[i64], limit: usize) -> Snapshot {
+    let mut program = Program::new(code, inputs);
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        for _ in 0..limit {
+            if !program.step() {
+                return if program.is_finished() {
+                    Outcome::Halted
+                } else {
+                    Outcome::AwaitingInput
+                };
+            }
+        }
+
+        Outcome::StepLimit
+    }));
+
+    Snapshot {
+        outcome: result.unwrap_or(Outcome::Faulted),
+        memory: program.code.clone(),
+        output: program.all_output().to_vec(),
+    }
+}
+
+/**
+ * Runs both interpreters for at most `limit` instructions and checks that they
+ * stop the same way, with the same memory and output.
+ */
+pub fn compare(
+    code: &[i64],
+    inputs: &[i64],
+    limit: usize,
+) -> Result<Snapshot, Box<Divergence>> {
+    let (reference, fault) = run_reference(code, inputs, limit);
+    let program = run_program(code, inputs, limit);
+
+    if reference == program {
+        Ok(reference)
+    } else {
+        Err(Box::new(Divergence {
+            reference,
+            program,
+            fault,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use proptest::prelude::*;
+
+    const LIMIT: usize = 100_000;
+
+    fn parse(text: &str) -> Vec<i64> {
+        text.trim()
+            .split(',')
+            .map(|node| node.parse::<i64>().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_machine() {
+        let (snapshot, fault) =
+            run_reference(&[1, 9, 10, 3, 2, 3, 11, 0, 99, 30, 40, 50], &[], LIMIT);
+
+        assert_eq!(snapshot.outcome, Outcome::Halted);
+        assert_eq!(
+            snapshot.memory,
+            vec![3500, 9, 10, 70, 2, 3, 11, 0, 99, 30, 40, 50]
+        );
+        assert_eq!(fault, None);
+
+        let quine = [
+            109, 1, 204, -1, 1001, 100, 1, 100, 1008, 100, 16, 101, 1006, 101, 0, 99,
+        ];
+        let (snapshot, _) = run_reference(&quine, &[], LIMIT);
+
+        assert_eq!(snapshot.output, quine.to_vec());
+    }
+
+    #[test]
+    fn test_machine_awaits_input() {
+        let mut machine = Machine::new(&[3, 5, 4, 5, 99, 0], &[]);
+
+        assert_eq!(machine.step(), State::AwaitingInput);
+
+        machine.send_input(42);
+
+        assert_eq!(machine.step(), State::Running);
+        assert_eq!(machine.step(), State::Running);
+        assert_eq!(machine.step(), State::Halted);
+        assert_eq!(machine.output, vec![42]);
+    }
+
+    #[test]
+    fn test_machine_faults() {
+        let fault = |code: &[i64]| run_reference(code, &[], LIMIT).1;
+
+        assert_eq!(
+            fault(&[42]),
+            Some(Fault::InvalidOpcode { pos: 0, value: 42 })
+        );
+        assert_eq!(
+            fault(&[301, 0, 0, 0, 99]),
+            Some(Fault::InvalidMode { pos: 0, value: 301 })
+        );
+        assert_eq!(
+            fault(&[11101, 1, 1, 5, 99]),
+            Some(Fault::ImmediateWrite { pos: 0 })
+        );
+        assert_eq!(
+            fault(&[1, -1, 0, 0, 99]),
+            Some(Fault::NegativeAddress {
+                pos: 0,
+                address: -1
+            })
+        );
+        assert_eq!(
+            fault(&[1102, i64::MAX, 2, 0, 99]),
+            Some(Fault::Overflow { pos: 0 })
+        );
+    }
+
+    #[test]
+    fn test_compare_puzzle_programs() {
+        let d05 = parse(include_str!("../data/d05.txt"));
+        let d07 = parse(include_str!("../data/d07.txt"));
+        let d09 = parse(include_str!("../data/d09.txt"));
+
+        assert!(compare(&d05, &[1], LIMIT).is_ok());
+        assert!(compare(&d05, &[5], LIMIT).is_ok());
+        assert!(compare(&d07, &[4, 0], LIMIT).is_ok());
+        assert!(compare(&d07, &[], LIMIT).is_ok());
+        assert_eq!(
+            compare(&d09, &[1], LIMIT).unwrap().output,
+            vec![3_780_860_499]
+        );
+        assert_eq!(
+            compare(&d09, &[2], 1000).unwrap().outcome,
+            Outcome::StepLimit
+        );
+    }
+
+    #[test]
+    fn test_compare_catches_ignored_writes() {
+        // `Program` skips the write, where the reference refuses to run it.
+        let divergence = compare(&[11101, 1, 1, 5, 4, 5, 99], &[], LIMIT).unwrap_err();
+
+        assert_eq!(divergence.reference.outcome, Outcome::Faulted);
+        assert_eq!(divergence.program.outcome, Outcome::Halted);
+        assert_eq!(divergence.fault, Some(Fault::ImmediateWrite { pos: 0 }));
+
+        // Unknown modes make `Program` drop parameters.
+        assert!(compare(&[30101, 1, 1, 5, 99, 0], &[], LIMIT).is_err());
+    }
+
+    // The pieces random programs are built from. Data slots and jumps are
+    // resolved to addresses when the program is assembled.
+    #[derive(Clone, Debug)]
+    enum Arg {
+        Position(usize),
+        Immediate(i64),
+        Relative(usize),
+    }
+
+    #[derive(Clone, Debug)]
+    enum Target {
+        Position(usize),
+        Relative(usize),
+    }
+
+    #[derive(Clone, Debug)]
+    enum Op {
+        Arithmetic(i64, Arg, Arg, Target),
+        Input(Target),
+        Output(Arg),
+        Jump(i64, Arg, usize),
+        AdjustBase(i64),
+    }
+
+    const DATA_SLOTS: usize = 8;
+
+    impl Op {
+        fn length(&self) -> usize {
+            match self {
+                Op::Arithmetic(..) => 4,
+                Op::Input(_) | Op::Output(_) | Op::AdjustBase(_) => 2,
+                Op::Jump(..) => 3,
+            }
+        }
+    }
+
+    fn arg() -> impl Strategy<Value = Arg> {
+        prop_oneof![
+            (0..DATA_SLOTS).prop_map(Arg::Position),
+            (-1000..1000i64).prop_map(Arg::Immediate),
+            (0..DATA_SLOTS).prop_map(Arg::Relative),
+        ]
+    }
+
+    fn target() -> impl Strategy<Value = Target> {
+        prop_oneof![
+            (0..DATA_SLOTS).prop_map(Target::Position),
+            (0..DATA_SLOTS).prop_map(Target::Relative),
+        ]
+    }
+
+    fn op() -> impl Strategy<Value = Op> {
+        prop_oneof![
+            (
+                prop_oneof![Just(1), Just(2), Just(7), Just(8)],
+                arg(),
+                arg(),
+                target()
+            )
+                .prop_map(|(opcode, a, b, t)| Op::Arithmetic(opcode, a, b, t)),
+            target().prop_map(Op::Input),
+            arg().prop_map(Op::Output),
+            (prop_oneof![Just(5), Just(6)], arg(), 1..4usize)
+                .prop_map(|(opcode, a, skip)| Op::Jump(opcode, a, skip)),
+            (0..4i64).prop_map(Op::AdjustBase),
+        ]
+    }
+
+    /**
+     * Lays out the ops, a halt and the data slots. Jumps only go forward and the
+     * relative base only grows, so every program halts (or waits for input) and
+     * never writes over its own code.
+     */
+    fn assemble(ops: &[Op], data: &[i64]) -> Vec<i64> {
+        let mut addresses = vec![0];
+        for op in ops {
+            addresses.push(addresses[addresses.len() - 1] + op.length());
+        }
+
+        let data_start = (addresses[ops.len()] + 1) as i64;
+
+        let arg = |arg: &Arg| match arg {
+            Arg::Position(slot) => (0, data_start + *slot as i64),
+            Arg::Immediate(value) => (1, *value),
+            Arg::Relative(slot) => (2, data_start + *slot as i64),
+        };
+        let target = |target: &Target| match target {
+            Target::Position(slot) => (0, data_start + *slot as i64),
+            Target::Relative(slot) => (2, data_start + *slot as i64),
+        };
+
+        let mut code = vec![];
+
+        for (i, op) in ops.iter().enumerate() {
+            let (opcode, params) = match op {
+                Op::Arithmetic(opcode, a, b, t) => {
+                    (*opcode, vec![arg(a), arg(b), target(t)])
+                }
+                Op::Input(t) => (3, vec![target(t)]),
+                Op::Output(a) => (4, vec![arg(a)]),
+                Op::Jump(opcode, a, skip) => {
+                    let to = addresses[(i + skip).min(ops.len())] as i64;
+                    (*opcode, vec![arg(a), (1, to)])
+                }
+                Op::AdjustBase(by) => (9, vec![(1, *by)]),
+            };
+
+            let modes = params
+                .iter()
+                .enumerate()
+                .map(|(n, (mode, _))| mode * 10_i64.pow(n as u32 + 2))
+                .sum::<i64>();
+
+            code.push(opcode + modes);
+            code.extend(params.iter().map(|(_, value)| *value));
+        }
+
+        code.push(99);
+        code.extend_from_slice(data);
+
+        code
+    }
+
+    proptest! {
+        #[test]
+        fn prop_valid_programs_agree(
+            ops in proptest::collection::vec(op(), 0..32),
+            data in proptest::collection::vec(-100..100i64, DATA_SLOTS),
+            inputs in proptest::collection::vec(-100..100i64, 0..8),
+        ) {
+            let code = assemble(&ops, &data);
+
+            let (snapshot, fault) = run_reference(&code, &inputs, LIMIT);
+
+            // Overflow is the only fault a valid program can run into.
+            let overflowed_or_clean = matches!(fault, None | Some(Fault::Overflow { .. }));
+            prop_assert!(overflowed_or_clean);
+            prop_assert_ne!(snapshot.outcome, Outcome::StepLimit);
+
+            if let Err(divergence) = compare(&code, &inputs, LIMIT) {
+                prop_assert!(false, "{} for {:?}", divergence, code);
+            }
+        }
+
+        #[test]
+        fn prop_arbitrary_words_agree_when_reference_runs_cleanly(
+            code in proptest::collection::vec(prop_oneof![0..10i64, 0..2300i64, -5..5i64], 1..40),
+            inputs in proptest::collection::vec(-100..100i64, 0..4),
+        ) {
+            let (snapshot, fault) = run_reference(&code, &inputs, 1000);
+
+            // The reference is the judge here: whatever it runs without a fault,
+            // `Program` has to run the same way.
+            if fault.is_none() {
+                prop_assert_eq!(snapshot, run_program(&code, &inputs, 1000));
+            }
+        }
+    }
+}
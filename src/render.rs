@@ -0,0 +1,793 @@
+use crate::asteroids::{InvalidCoordinateError, Obliteration, Region};
+use crate::grid::{Coordinate, Grid};
+use rayon::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+
+/**
+ * Stroke colours for the wires of an SVG, reused in order when there are more wires.
+ */
+pub const PALETTE: [&str; 6] = [
+    "#d62728", "#1f77b4", "#2ca02c", "#ff7f0e", "#9467bd", "#8c564b",
+];
+
+/**
+ * The part of the grid to draw, from `min` to `max` inclusive. Each character (or SVG
+ * pixel) covers `scale` by `scale` cells.
+ */
+#[derive(Clone, Debug, PartialEq)]
+pub struct Viewport {
+    pub min: Coordinate,
+    pub max: Coordinate,
+    pub scale: usize,
+}
+
+impl Viewport {
+    pub fn new(min: Coordinate, max: Coordinate) -> Self {
+        Viewport { min, max, scale: 1 }
+    }
+
+    /**
+     * The smallest viewport showing the origin and every wire.
+     */
+    pub fn around(grid: &Grid) -> Self {
+        let origin = grid.origin();
+        let (mut min, mut max) = (origin.clone(), origin);
+
+        for wire in 0..grid.count() {
+            let start = grid.start(wire);
+            let bounds = grid.segments(wire).iter().map(|segment| segment.bounds());
+
+            for (low, high) in
+                bounds.chain(std::iter::once((start.clone(), start.clone())))
+            {
+                min.x = min.x.min(low.x);
+                min.y = min.y.min(low.y);
+                max.x = max.x.max(high.x);
+                max.y = max.y.max(high.y);
+            }
+        }
+
+        Viewport::new(min, max)
+    }
+
+    pub fn scaled(self, scale: usize) -> Self {
+        Viewport {
+            scale: scale.max(1),
+            ..self
+        }
+    }
+
+    /**
+     * Scales the viewport down just enough to fit in `columns` by `rows`.
+     */
+    pub fn fit(self, columns: usize, rows: usize) -> Self {
+        let scale = |cells: usize, space: usize| cells.div_ceil(space.max(1));
+        let scale = scale(self.width(), columns).max(scale(self.height(), rows));
+
+        self.scaled(scale)
+    }
+
+    pub fn width(&self) -> usize {
+        (self.max.x - self.min.x + 1).max(0) as usize
+    }
+
+    pub fn height(&self) -> usize {
+        (self.max.y - self.min.y + 1).max(0) as usize
+    }
+
+    pub fn columns(&self) -> usize {
+        self.width().div_ceil(self.scale)
+    }
+
+    pub fn rows(&self) -> usize {
+        self.height().div_ceil(self.scale)
+    }
+
+    pub fn contains(&self, c: &Coordinate) -> bool {
+        (self.min.x..=self.max.x).contains(&c.x)
+            && (self.min.y..=self.max.y).contains(&c.y)
+    }
+
+    // The column and row drawing `c`, which must be inside the viewport.
+    fn cell(&self, c: &Coordinate) -> (usize, usize) {
+        (
+            (c.x - self.min.x) as usize / self.scale,
+            (c.y - self.min.y) as usize / self.scale,
+        )
+    }
+}
+
+// Later marks win when they land on the same character.
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+enum Mark {
+    Line(char),
+    Turn,
+    Crossing,
+    Origin,
+}
+
+impl Mark {
+    fn symbol(self) -> char {
+        match self {
+            Mark::Line(ch) => ch,
+            Mark::Turn => '+',
+            Mark::Crossing => 'X',
+            Mark::Origin => 'o',
+        }
+    }
+}
+
+fn line(delta: (isize, isize)) -> char {
+    match delta {
+        (0, _) => '|',
+        (_, 0) => '-',
+        // Up is -y, so down and right together run like a backslash.
+        (dx, dy) if dx.abs() == dy.abs() && (dx > 0) == (dy > 0) => '\\',
+        (dx, dy) if dx.abs() == dy.abs() => '/',
+        _ => '*',
+    }
+}
+
+struct Canvas {
+    cells: Vec<Vec<Option<(Mark, usize)>>>,
+}
+
+impl Canvas {
+    fn new(viewport: &Viewport) -> Self {
+        Canvas {
+            cells: vec![vec![None; viewport.columns()]; viewport.rows()],
+        }
+    }
+
+    fn put(&mut self, (column, row): (usize, usize), mark: Mark, wire: usize) {
+        let cell = &mut self.cells[row][column];
+
+        *cell = Some(match *cell {
+            None => (mark, wire),
+            Some((old, first)) if first != wire => (old.max(Mark::Crossing), first),
+            Some((old, first)) if old == mark => (old, first),
+            Some((old, first)) => (old.max(mark).max(Mark::Turn), first),
+        });
+    }
+
+    fn render(&self) -> String {
+        self.cells
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|cell| cell.map_or(' ', |(mark, _)| mark.symbol()))
+                    .chain(std::iter::once('\n'))
+                    .collect::<String>()
+            })
+            .collect()
+    }
+}
+
+/**
+ * Draws the wires inside `viewport`: `-`, `|`, `/` and `\` along a wire, `+` where it
+ * turns, `X` where wires cross and `o` where they start.
+ */
+pub fn ascii(grid: &Grid, viewport: &Viewport) -> String {
+    let mut canvas = Canvas::new(viewport);
+
+    for wire in 0..grid.count() {
+        let segments = grid.segments(wire);
+
+        for segment in segments {
+            let mark = Mark::Line(line(segment.delta));
+
+            if let Some((from, to)) = segment.indices_within(&viewport.min, &viewport.max)
+            {
+                for i in from..=to {
+                    canvas.put(viewport.cell(&segment.point(i)), mark, wire);
+                }
+            }
+        }
+
+        for pair in segments.windows(2) {
+            if pair[0].delta != pair[1].delta && viewport.contains(&pair[1].start) {
+                canvas.put(viewport.cell(&pair[1].start), Mark::Turn, wire);
+            }
+        }
+    }
+
+    for wire in 0..grid.count() {
+        let start = grid.start(wire);
+
+        if viewport.contains(start) {
+            let (column, row) = viewport.cell(start);
+            canvas.cells[row][column] = Some((Mark::Origin, wire));
+        }
+    }
+
+    canvas.render()
+}
+
+/**
+ * An SVG of the wires, one colour each, with the pairwise crossings circled. Only the
+ * part inside `viewport` is shown, at one pixel per `scale` cells.
+ */
+pub fn svg(grid: &Grid, viewport: &Viewport) -> String {
+    let (min, scale) = (&viewport.min, viewport.scale as f64);
+    let mut svg = String::new();
+
+    writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{} {} {} {}" width="{}" height="{}">"#,
+        min.x as f64 - 0.5,
+        min.y as f64 - 0.5,
+        viewport.width(),
+        viewport.height(),
+        viewport.columns(),
+        viewport.rows()
+    )
+    .unwrap();
+
+    for wire in 0..grid.count() {
+        let segments = grid.segments(wire);
+
+        if segments.is_empty() {
+            continue;
+        }
+
+        let points = std::iter::once(grid.start(wire).clone())
+            .chain(segments.iter().map(|segment| segment.end()))
+            .map(|c| format!("{},{}", c.x, c.y))
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        writeln!(
+            svg,
+            r#"  <polyline points="{}" fill="none" stroke="{}" stroke-width="1" vector-effect="non-scaling-stroke"/>"#,
+            points,
+            PALETTE[wire % PALETTE.len()]
+        )
+        .unwrap();
+    }
+
+    for crossing in grid.pairwise_crossings() {
+        if viewport.contains(&crossing.at) {
+            writeln!(
+                svg,
+                r#"  <circle cx="{}" cy="{}" r="{}" fill="none" stroke="black" vector-effect="non-scaling-stroke"/>"#,
+                crossing.at.x,
+                crossing.at.y,
+                scale * 2.0
+            )
+            .unwrap();
+        }
+    }
+
+    let mut starts = (0..grid.count())
+        .map(|wire| grid.start(wire))
+        .filter(|start| viewport.contains(start))
+        .collect::<Vec<_>>();
+    starts.sort_by_key(|start| (start.x, start.y));
+    starts.dedup();
+
+    for start in starts {
+        writeln!(
+            svg,
+            r#"  <circle cx="{}" cy="{}" r="{}" fill="black"/>"#,
+            start.x,
+            start.y,
+            scale * 2.0
+        )
+        .unwrap();
+    }
+
+    svg.push_str("</svg>\n");
+
+    svg
+}
+
+/**
+ * How many others each asteroid in the region can see.
+ */
+pub fn visible_counts(region: &Region) -> HashMap<Coordinate, usize> {
+    region
+        .coordinates
+        .par_iter()
+        .map(|c| (c.clone(), region.visible_count_from(c)))
+        .collect()
+}
+
+// Blue for the lowest count through to red for the highest.
+fn heat(count: usize, max: usize) -> (u8, u8, u8) {
+    let t = if max == 0 {
+        0.0
+    } else {
+        count as f64 / max as f64
+    };
+    let mix = |low: f64, high: f64| (low + (high - low) * t).round() as u8;
+
+    (mix(49.0, 215.0), mix(54.0, 48.0), mix(149.0, 39.0))
+}
+
+// One line per row of the region, the cells right-aligned to the widest and
+// separated by a space when any is wider than a character.
+fn table(region: &Region, cell: impl Fn(&Coordinate) -> String) -> String {
+    let rows = (0..region.height)
+        .map(|y| {
+            (0..region.width)
+                .map(|x| cell(&Coordinate::new(x as isize, y as isize)))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let width = rows
+        .iter()
+        .flatten()
+        .map(|cell| cell.chars().count())
+        .max()
+        .unwrap_or(1);
+    let separator = if width > 1 { " " } else { "" };
+
+    rows.iter()
+        .map(|row| {
+            let row = row
+                .iter()
+                .map(|cell| format!("{:>1$}", cell, width))
+                .collect::<Vec<_>>();
+
+            row.join(separator) + "\n"
+        })
+        .collect()
+}
+
+// The map character for anything that isn't an asteroid.
+fn background(region: &Region, c: &Coordinate) -> char {
+    let cells = &region.cells;
+
+    if region.obstacles.contains(c) {
+        cells.obstacle
+    } else {
+        cells.empty
+    }
+}
+
+/**
+ * The region with each asteroid replaced by how many others it can see.
+ */
+pub fn field_counts(region: &Region) -> String {
+    let counts = visible_counts(region);
+
+    table(region, |c| match counts.get(c) {
+        Some(count) => count.to_string(),
+        None => background(region, c).to_string(),
+    })
+}
+
+/**
+ * The region as drawn in the puzzle, each asteroid coloured with ANSI escapes from
+ * blue for the fewest visible to red for the most.
+ */
+pub fn field_ansi(region: &Region) -> String {
+    let counts = visible_counts(region);
+    let max = counts.values().copied().max().unwrap_or(0);
+    let cells = &region.cells;
+    let mut text = String::new();
+
+    for y in 0..region.height {
+        for x in 0..region.width {
+            let c = Coordinate::new(x as isize, y as isize);
+
+            match counts.get(&c) {
+                Some(count) => {
+                    let (r, g, b) = heat(*count, max);
+                    let symbol = if region.station.as_ref() == Some(&c) {
+                        cells.station
+                    } else {
+                        cells.asteroid
+                    };
+
+                    write!(text, "\x1b[38;2;{};{};{}m{}\x1b[0m", r, g, b, symbol)
+                        .unwrap();
+                }
+                None => text.push(background(region, &c)),
+            }
+        }
+
+        text.push('\n');
+    }
+
+    text
+}
+
+/**
+ * What `station` can see: `*` for the asteroids in sight, the region's asteroid symbol
+ * for the hidden ones and its station symbol for the station itself.
+ */
+pub fn field_sightlines(
+    region: &Region,
+    station: &Coordinate,
+) -> Result<String, InvalidCoordinateError> {
+    let visible = region
+        .visible_from(station)?
+        .into_iter()
+        .collect::<HashSet<_>>();
+    let cells = &region.cells;
+
+    Ok(table(region, |c| {
+        if c == station {
+            cells.station
+        } else if visible.contains(c) {
+            '*'
+        } else if region.contains(c) {
+            cells.asteroid
+        } else {
+            background(region, c)
+        }
+        .to_string()
+    }))
+}
+
+/**
+ * The region with each asteroid numbered by when `obliteration` vaporizes it,
+ * counting from 1. Asteroids it never reaches keep their symbol.
+ */
+pub fn field_order(region: &Region, obliteration: &Obliteration) -> String {
+    let order = obliteration
+        .order()
+        .iter()
+        .enumerate()
+        .map(|(i, c)| (c, i + 1))
+        .collect::<HashMap<_, _>>();
+    let cells = &region.cells;
+
+    table(region, |c| match order.get(c) {
+        Some(turn) => turn.to_string(),
+        None if c == obliteration.from() => cells.station.to_string(),
+        None if region.contains(c) => cells.asteroid.to_string(),
+        None => background(region, c).to_string(),
+    })
+}
+
+/**
+ * A plain (P3) PPM heatmap of the visible counts, `scale` pixels to a cell. Empty
+ * space is black, obstacles grey and a marked station white.
+ */
+pub fn field_ppm(region: &Region, scale: usize) -> String {
+    let counts = visible_counts(region);
+    let max = counts.values().copied().max().unwrap_or(0);
+    let scale = scale.max(1);
+    let mut ppm = String::new();
+
+    writeln!(
+        ppm,
+        "P3\n{} {}\n255",
+        region.width * scale,
+        region.height * scale
+    )
+    .unwrap();
+
+    for y in 0..region.height {
+        let row = (0..region.width)
+            .map(|x| {
+                let c = Coordinate::new(x as isize, y as isize);
+                let (r, g, b) = match counts.get(&c) {
+                    _ if region.station.as_ref() == Some(&c) => (255, 255, 255),
+                    Some(count) => heat(*count, max),
+                    None if region.obstacles.contains(&c) => (128, 128, 128),
+                    None => (0, 0, 0),
+                };
+
+                vec![format!("{} {} {}", r, g, b); scale].join(" ")
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        for _ in 0..scale {
+            writeln!(ppm, "{}", row).unwrap();
+        }
+    }
+
+    ppm
+}
+
+/**
+ * An SVG heatmap of the visible counts. Hovering over an asteroid shows its count.
+ * With a `station`, a line runs to every asteroid it can see.
+ */
+pub fn field_svg(
+    region: &Region,
+    station: Option<&Coordinate>,
+) -> Result<String, InvalidCoordinateError> {
+    let counts = visible_counts(region);
+    let max = counts.values().copied().max().unwrap_or(0);
+    let mut svg = String::new();
+
+    writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="-0.5 -0.5 {} {}" width="{}" height="{}">"#,
+        region.width,
+        region.height,
+        region.width * 10,
+        region.height * 10
+    )
+    .unwrap();
+
+    let mut obstacles = region.obstacles.iter().collect::<Vec<_>>();
+    obstacles.sort();
+
+    for c in obstacles {
+        writeln!(
+            svg,
+            r#"  <rect x="{}" y="{}" width="1" height="1" fill="grey"/>"#,
+            c.x as f64 - 0.5,
+            c.y as f64 - 0.5
+        )
+        .unwrap();
+    }
+
+    if let Some(station) = station {
+        let mut visible = region.visible_from(station)?;
+        visible.sort();
+
+        for c in visible {
+            writeln!(
+                svg,
+                r#"  <line x1="{}" y1="{}" x2="{}" y2="{}" stroke="black" stroke-width="1" vector-effect="non-scaling-stroke"/>"#,
+                station.x, station.y, c.x, c.y
+            )
+            .unwrap();
+        }
+    }
+
+    let mut asteroids = counts.into_iter().collect::<Vec<_>>();
+    asteroids.sort();
+
+    for (c, count) in asteroids {
+        let (r, g, b) = heat(count, max);
+        let stroke = if station == Some(&c) {
+            r#" stroke="black" stroke-width="0.15""#
+        } else {
+            ""
+        };
+
+        writeln!(
+            svg,
+            r#"  <circle cx="{}" cy="{}" r="0.4" fill="rgb({},{},{})"{}><title>{},{}: {}</title></circle>"#,
+            c.x, c.y, r, g, b, stroke, c.x, c.y, count
+        )
+        .unwrap();
+    }
+
+    svg.push_str("</svg>\n");
+
+    Ok(svg)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::asteroids::{CellMap, Rotation};
+    use crate::grid::Route;
+
+    fn grid(routes: &[&str]) -> Grid {
+        Grid::from(
+            &routes
+                .iter()
+                .map(|route| route.parse::<Route>().unwrap())
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    #[test]
+    fn test_ascii() {
+        let grid = grid(&["R8,U5,L5,D3", "U7,R6,D4,L4"]);
+        let viewport = Viewport::around(&grid);
+
+        assert_eq!(
+            viewport,
+            Viewport::new(Coordinate { x: 0, y: -7 }, Coordinate { x: 8, y: 0 })
+        );
+        assert_eq!(
+            ascii(&grid, &viewport),
+            [
+                "+-----+  ",
+                "|     |  ",
+                "|  +--X-+",
+                "|  |  | |",
+                "| -X--+ |",
+                "|  |    |",
+                "|       |",
+                "o-------+",
+                "",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn test_ascii_diagonals() {
+        let grid = grid(&["SE2,NE2"]);
+
+        assert_eq!(
+            ascii(&grid, &Viewport::around(&grid)),
+            "o   /\n \\ / \n  +  \n"
+        );
+    }
+
+    #[test]
+    fn test_ascii_cropped() {
+        let grid = grid(&["R8,U5,L5,D3", "U7,R6,D4,L4"]);
+        let viewport =
+            Viewport::new(Coordinate { x: 2, y: -5 }, Coordinate { x: 6, y: -3 });
+
+        assert_eq!(ascii(&grid, &viewport), " +--X\n |  |\n-X--+\n");
+    }
+
+    #[test]
+    fn test_ascii_scaled() {
+        let grid = grid(&["R1000,U500", "U300,R2000"]);
+        let viewport = Viewport::around(&grid).fit(40, 20);
+
+        assert_eq!(viewport.scale, 51);
+        assert!(viewport.columns() <= 40 && viewport.rows() <= 20);
+
+        let text = ascii(&grid, &viewport);
+
+        assert_eq!(text.lines().count(), viewport.rows());
+        assert_eq!(text.matches('X').count(), 1);
+        assert_eq!(text.lines().last().unwrap().chars().next(), Some('o'));
+    }
+
+    #[test]
+    fn test_ascii_starts() {
+        let mut grid = Grid::default();
+        grid.add_route(&"R4".parse().unwrap());
+        grid.add_route_at(&"U2".parse().unwrap(), Coordinate { x: 2, y: 1 });
+
+        assert_eq!(
+            ascii(&grid, &Viewport::around(&grid)),
+            "  |  \no-X--\n  o  \n"
+        );
+    }
+
+    #[test]
+    fn test_svg() {
+        let grid = grid(&["R8,U5,L5,D3", "U7,R6,D4,L4"]);
+        let svg = svg(&grid, &Viewport::around(&grid));
+
+        assert!(svg.starts_with(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="-0.5 -7.5 9 8" width="9" height="8">"#
+        ));
+        assert!(svg.contains(
+            r##"points="0,0 8,0 8,-5 3,-5 3,-2" fill="none" stroke="#d62728""##
+        ));
+        assert!(svg.contains(
+            r##"points="0,0 0,-7 6,-7 6,-3 2,-3" fill="none" stroke="#1f77b4""##
+        ));
+        assert_eq!(svg.matches("<circle").count(), 3);
+        assert!(svg.ends_with("</svg>\n"));
+    }
+
+    const FIELD: &str = ".#..#\n.....\n#####\n....#\n...##";
+
+    #[test]
+    fn test_field_counts() {
+        let region = Region::from(FIELD);
+
+        assert_eq!(field_counts(&region), ".7..7\n.....\n67775\n....7\n...87\n");
+        assert_eq!(
+            visible_counts(&region)
+                .into_iter()
+                .max_by_key(|(_, count)| *count),
+            Some((Coordinate::new(3, 4), 8))
+        );
+    }
+
+    #[test]
+    fn test_field_ansi() {
+        let region = Region::parse(&FIELD.replace("...##", "...X#")).unwrap();
+        let text = field_ansi(&region);
+
+        assert_eq!(text.lines().count(), 5);
+        assert!(text.contains("\x1b[38;2;215;48;39mX\x1b[0m"));
+        assert_eq!(text.matches("\x1b[0m").count(), region.len());
+        assert!(text.starts_with(".\x1b[38;2;"));
+    }
+
+    #[test]
+    fn test_field_sightlines() {
+        let region = Region::from(FIELD);
+
+        assert_eq!(
+            field_sightlines(&region, &Coordinate::new(3, 4)).unwrap(),
+            ".#..*\n.....\n*****\n....*\n...X*\n"
+        );
+        assert!(field_sightlines(&region, &Coordinate::new(0, 0)).is_err());
+    }
+
+    #[test]
+    fn test_field_custom_cells() {
+        let cells = CellMap {
+            empty: '-',
+            asteroid: 'o',
+            station: 'S',
+            obstacle: '%',
+        };
+        let region = Region::parse_with("-o-%\n-S-o", &cells).unwrap();
+
+        assert_eq!(field_counts(&region), "-2-%\n-2-2\n");
+        assert_eq!(
+            field_sightlines(&region, &Coordinate::new(1, 1)).unwrap(),
+            "-*-%\n-S-*\n"
+        );
+        assert!(field_ansi(&region).contains("mS\x1b[0m"));
+
+        let obliteration = Obliteration::new(&region, &Coordinate::new(1, 1)).unwrap();
+
+        assert_eq!(field_order(&region, &obliteration), "-1-%\n-S-2\n");
+    }
+
+    #[test]
+    fn test_field_order() {
+        let region = Region::from("..#..\n..#..\n#.#.#\n..#..");
+        let obliteration = Obliteration::new(&region, &Coordinate::new(2, 2)).unwrap();
+
+        assert_eq!(
+            field_order(&region, &obliteration),
+            "..5..\n..1..\n4.X.2\n..3..\n"
+        );
+
+        let region = Region::parse("#.@.#.#\n#......").unwrap();
+        let obliteration = Obliteration::new(&region, &Coordinate::new(4, 0)).unwrap();
+
+        assert_eq!(field_order(&region, &obliteration), "#.@.X.1\n2......\n");
+
+        let region = Region::from("####\n####\n####\n####");
+        let obliteration = Obliteration::with_rotation(
+            &region,
+            &Coordinate::new(0, 0),
+            &Coordinate::new(0, -1),
+            Rotation::Clockwise,
+        )
+        .unwrap();
+
+        assert_eq!(
+            field_order(&region, &obliteration).lines().next(),
+            Some(" X  1 10 13")
+        );
+    }
+
+    #[test]
+    fn test_field_ppm() {
+        let region = Region::parse("#@\n.X").unwrap();
+        let ppm = field_ppm(&region, 2);
+
+        assert!(ppm.starts_with("P3\n4 4\n255\n"));
+        assert_eq!(ppm.lines().count(), 7);
+        assert_eq!(
+            ppm.lines().nth(3),
+            Some("215 48 39 215 48 39 128 128 128 128 128 128")
+        );
+        assert_eq!(
+            ppm.lines().last(),
+            Some("0 0 0 0 0 0 255 255 255 255 255 255")
+        );
+    }
+
+    #[test]
+    fn test_field_svg() {
+        let region = Region::from(FIELD);
+        let station = Coordinate::new(3, 4);
+        let svg = field_svg(&region, Some(&station)).unwrap();
+
+        assert!(svg.starts_with(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="-0.5 -0.5 5 5" width="50" height="50">"#
+        ));
+        assert_eq!(svg.matches("<circle").count(), 10);
+        assert_eq!(svg.matches("<line").count(), 8);
+        assert!(svg.contains("<title>3,4: 8</title>"));
+        assert!(svg.ends_with("</svg>\n"));
+
+        assert_eq!(
+            field_svg(&region, None).unwrap().matches("<line").count(),
+            0
+        );
+        assert!(field_svg(&region, Some(&Coordinate::new(0, 0))).is_err());
+    }
+}
@@ -1,4 +1,5 @@
-use rayon::prelude::*;
+use crate::grid2d::Grid2D;
+use crate::solution::{Answer, Solution};
 use std::collections::HashMap;
 use std::error::Error;
 use std::iter::Iterator;
@@ -7,7 +8,7 @@ use std::iter::Iterator;
 // const WHITE: u32 = 1;
 const TRANSPARENT: u32 = 2;
 
-pub type Grid = Vec<Vec<u32>>;
+pub type Grid = Grid2D<u32>;
 
 #[derive(Debug, Default)]
 pub struct Image {
@@ -26,27 +27,52 @@ impl Image {
         }
     }
 
+    /**
+     * Reads an image written as one digit per pixel, failing on anything else.
+     */
+    pub fn parse(
+        width: usize,
+        height: usize,
+        text: &str,
+    ) -> Result<Self, Box<dyn Error>> {
+        let digits = text
+            .trim()
+            .chars()
+            .map(|ch| {
+                ch.to_digit(10)
+                    .ok_or_else(|| format!("Invalid pixel: {}", ch))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Image {
+            width,
+            height,
+            layers: Self::parse_iterator(width, height, digits.into_iter())?,
+        })
+    }
+
     pub fn parse_iterator(
         width: usize,
         height: usize,
         source: impl Iterator<Item = u32>,
     ) -> Result<Vec<Grid>, Box<dyn Error>> {
-        let mut digits_vec = vec![];
-
-        for n in source {
-            digits_vec.push(n);
+        let digits_vec = source.collect::<Vec<_>>();
+        let layer_size = width * height;
+
+        if layer_size == 0 || digits_vec.is_empty() || digits_vec.len() % layer_size != 0
+        {
+            return Err(format!(
+                "{} pixels do not make whole {}x{} layers",
+                digits_vec.len(),
+                width,
+                height
+            )
+            .into());
         }
 
-        let chunks: Vec<_> = digits_vec.chunks(width).collect();
-        let chunks_of_chunks: Vec<_> = chunks.chunks(height).collect();
-
-        let layers = chunks_of_chunks
-            .into_par_iter()
-            .map(|grid_data| {
-                let vectorized_data: Vec<Vec<_>> =
-                    grid_data.into_par_iter().map(|d| d.to_vec()).collect();
-                vectorized_data
-            })
+        let layers = digits_vec
+            .chunks(layer_size)
+            .map(|layer| Grid2D::from_cells(width, height, layer.to_vec()))
             .collect();
 
         Ok(layers)
@@ -62,7 +88,7 @@ impl Image {
                 counts.insert(i, 0);
             }
 
-            for j in n.iter().flatten() {
+            for j in n.values() {
                 let val = *(counts.get(&j).get_or_insert(&0)) + 1;
                 counts.insert(*j, val);
             }
@@ -73,42 +99,52 @@ impl Image {
         all_counts
     }
 
-    pub fn visible(&self) -> Vec<Vec<u32>> {
-        let flattened_layers: Vec<Vec<u32>> = self
-            .layers
-            .clone()
-            .into_par_iter()
-            .map(|layer| layer.into_iter().flatten().collect())
-            .collect();
+    pub fn visible(&self) -> Grid {
+        let mut visible = Grid2D::new(self.width, self.height, TRANSPARENT);
 
-        let layer_size = flattened_layers[0].len();
-
-        flattened_layers
-            .into_iter()
-            .fold(
-                vec![TRANSPARENT; layer_size],
-                |mut memo: Vec<u32>, layer: Vec<u32>| {
-                    for (i, n) in layer.into_iter().enumerate() {
-                        memo[i] = if memo[i] != 2 { memo[i] } else { n };
-                    }
-                    memo
-                },
-            )
-            .chunks(self.width)
-            .map(|ch| ch.to_owned())
-            .collect()
+        for layer in &self.layers {
+            for (memo, n) in visible.values_mut().zip(layer.values()) {
+                if *memo == TRANSPARENT {
+                    *memo = *n;
+                }
+            }
+        }
+
+        visible
+    }
+
+    pub fn render(&self) -> String {
+        self.visible()
+            .map(|pixel| if *pixel == 1 { '\u{2588}' } else { ' ' })
+            .to_string()
     }
 
     pub fn print(&self) {
-        let visible = self.visible();
+        print!("{}", self.render());
+    }
+}
 
-        for row in visible {
-            for pixel in row {
-                let output = if pixel == 1 { '\u{2588}' } else { ' ' };
-                print!("{}", output);
-            }
-            println!();
-        }
+pub struct Day08;
+
+impl Solution for Day08 {
+    type Input = Image;
+
+    fn parse(input: &str) -> Result<Self::Input, Box<dyn Error>> {
+        Image::parse(25, 6, input)
+    }
+
+    fn part1(image: &Self::Input) -> Answer {
+        let counts = image.count_digits();
+        let layer = counts
+            .iter()
+            .min_by_key(|map| map[&0])
+            .ok_or("Image has no layers")?;
+
+        Ok((layer[&1] * layer[&2]).to_string())
+    }
+
+    fn part2(image: &Self::Input) -> Answer {
+        Ok(image.render())
     }
 }
 
@@ -120,12 +156,40 @@ mod test {
     #[test]
     fn test_parse_source() {
         assert_eq!(
-            Image::parse_iterator(3, 2, Digits::from(123_456_789_012u64)).unwrap(),
+            Image::parse_iterator(3, 2, Digits::from(123_456_789_012u64))
+                .unwrap()
+                .iter()
+                .map(Grid::to_rows)
+                .collect::<Vec<_>>(),
             vec![
                 (vec![vec![1, 2, 3], vec![4, 5, 6]]),
                 (vec![vec![7, 8, 9], vec![0, 1, 2]])
             ]
         );
+
+        assert!(Image::parse_iterator(3, 2, Digits::from(12_345_678u64)).is_err());
+    }
+
+    #[test]
+    fn test_parse() {
+        let image = Image::parse(3, 2, "123456789012\n").unwrap();
+
+        assert_eq!(image.layers.len(), 2);
+        assert_eq!(
+            image.layers[1].to_rows(),
+            vec![vec![7, 8, 9], vec![0, 1, 2]]
+        );
+
+        assert_eq!(
+            Image::parse(3, 2, "12345x").unwrap_err().to_string(),
+            "Invalid pixel: x"
+        );
+        assert_eq!(
+            Image::parse(3, 2, "1234567").unwrap_err().to_string(),
+            "7 pixels do not make whole 3x2 layers"
+        );
+        assert!(Image::parse(3, 2, " \n").is_err());
+        assert!(Day08::parse("0").is_err());
     }
 
     #[test]
@@ -167,7 +231,18 @@ mod test {
 
         let visible = image.visible();
 
-        assert_eq!(visible, vec![vec![0, 1], vec![1, 0]]);
+        assert_eq!(visible.to_rows(), vec![vec![0, 1], vec![1, 0]]);
+    }
+
+    #[test]
+    fn test_render() {
+        let input = "0222112222120000"
+            .chars()
+            .map(|node| node.to_digit(10).unwrap());
+
+        let image = Image::new(2, 2, input);
+
+        assert_eq!(image.render(), " \u{2588}\n\u{2588} \n");
     }
 
     #[test]
@@ -0,0 +1,84 @@
+use crate::{asteroids, code, fuel, grid, orbits, program, sif};
+use std::error::Error;
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub fn number(self) -> u32 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+}
+
+pub type Answer = Result<String, Box<dyn Error>>;
+
+/**
+ * A day's puzzle: how to read its input, and how to answer both parts from it.
+ */
+pub trait Solution {
+    type Input;
+
+    fn parse(input: &str) -> Result<Self::Input, Box<dyn Error>>;
+
+    fn part1(input: &Self::Input) -> Answer;
+
+    fn part2(input: &Self::Input) -> Answer;
+}
+
+pub type Runner = fn(&str, Part) -> Answer;
+
+pub fn solve<S: Solution>(input: &str, part: Part) -> Answer {
+    let parsed = S::parse(input)?;
+
+    match part {
+        Part::One => S::part1(&parsed),
+        Part::Two => S::part2(&parsed),
+    }
+}
+
+pub fn days() -> Vec<u32> {
+    (1..=10).collect()
+}
+
+pub fn runner(day: u32) -> Option<Runner> {
+    match day {
+        1 => Some(solve::<fuel::Day01>),
+        2 => Some(solve::<program::Day02>),
+        3 => Some(solve::<grid::Day03>),
+        4 => Some(solve::<code::Day04>),
+        5 => Some(solve::<program::Day05>),
+        6 => Some(solve::<orbits::Day06>),
+        7 => Some(solve::<program::Day07>),
+        8 => Some(solve::<sif::Day08>),
+        9 => Some(solve::<program::Day09>),
+        10 => Some(solve::<asteroids::Day10>),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_runner() {
+        assert!(runner(0).is_none());
+        assert!(runner(11).is_none());
+        assert!(days().into_iter().all(|day| runner(day).is_some()));
+    }
+
+    #[test]
+    fn test_solve() {
+        let run = runner(1).unwrap();
+
+        assert_eq!(run("12\n14\n1969\n", Part::One).unwrap(), "658");
+        assert_eq!(run("12\n14\n1969\n", Part::Two).unwrap(), "970");
+        assert!(run("12\nfourteen\n", Part::One).is_err());
+    }
+}
@@ -0,0 +1,626 @@
+use crate::program::{IMMEDIATE_MODE, OPCODE_LENGTHS, POSITION_MODE, RELATIVE_MODE};
+use std::collections::{BTreeMap, HashSet};
+use std::error::Error;
+use std::fmt::Write;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum TranspileError {
+    InvalidOpcode { pos: usize, value: i64 },
+    NegativeAddress { pos: usize },
+    DynamicJump { pos: usize },
+    DynamicWrite { pos: usize },
+    SelfModifying { pos: usize, target: usize },
+}
+
+impl Error for TranspileError {}
+
+impl std::fmt::Display for TranspileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TranspileError::InvalidOpcode { pos, value } => {
+                write!(f, "Invalid opcode {} at position {}", value, pos)
+            }
+            TranspileError::NegativeAddress { pos } => {
+                write!(f, "Negative address in instruction at position {}", pos)
+            }
+            TranspileError::DynamicJump { pos } => {
+                write!(
+                    f,
+                    "Jump target at position {} is not known ahead of time",
+                    pos
+                )
+            }
+            TranspileError::DynamicWrite { pos } => {
+                write!(
+                    f,
+                    "Write target at position {} is not known ahead of time",
+                    pos
+                )
+            }
+            TranspileError::SelfModifying { pos, target } => write!(
+                f,
+                "Instruction at position {} writes to code at position {}",
+                pos, target
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum Param {
+    Position(usize),
+    Immediate(i64),
+    Relative(i64),
+}
+
+#[derive(Debug)]
+struct Decoded {
+    pos: usize,
+    number: i64,
+    params: Vec<Param>,
+    length: usize,
+}
+
+impl Decoded {
+    fn next(&self) -> usize {
+        self.pos + self.length
+    }
+
+    // The parameter this instruction writes to, if any.
+    fn write_param(&self) -> Option<Param> {
+        match self.number {
+            1 | 2 | 7 | 8 => Some(self.params[2]),
+            3 => Some(self.params[0]),
+            _ => None,
+        }
+    }
+}
+
+fn word(code: &[i64], pos: usize) -> i64 {
+    code.get(pos).copied().unwrap_or(0)
+}
+
+fn decode(code: &[i64], pos: usize) -> Result<Decoded, TranspileError> {
+    let value = word(code, pos);
+    let invalid = TranspileError::InvalidOpcode { pos, value };
+
+    if value < 0 {
+        return Err(invalid);
+    }
+
+    let number = value % 100;
+    let length = *OPCODE_LENGTHS.get(&number).ok_or_else(|| invalid.clone())?;
+
+    let mut modes = value / 100;
+    let mut params = Vec::with_capacity(length - 1);
+
+    for offset in 1..length {
+        let raw = word(code, pos + offset);
+
+        let param = match (modes % 10) as u32 {
+            POSITION_MODE if raw < 0 => {
+                return Err(TranspileError::NegativeAddress { pos })
+            }
+            POSITION_MODE => Param::Position(raw as usize),
+            IMMEDIATE_MODE => Param::Immediate(raw),
+            RELATIVE_MODE => Param::Relative(raw),
+            _ => return Err(invalid),
+        };
+
+        params.push(param);
+        modes /= 10;
+    }
+
+    if modes != 0 {
+        return Err(invalid);
+    }
+
+    Ok(Decoded {
+        pos,
+        number,
+        params,
+        length,
+    })
+}
+
+// Resolves a jump target when it can be known without running the program.
+fn jump_target(code: &[i64], instruction: &Decoded) -> Result<usize, TranspileError> {
+    let pos = instruction.pos;
+
+    let target = match instruction.params[1] {
+        Param::Immediate(value) => value,
+        Param::Position(index) => word(code, index),
+        Param::Relative(_) => return Err(TranspileError::DynamicJump { pos }),
+    };
+
+    if target < 0 {
+        return Err(TranspileError::NegativeAddress { pos });
+    }
+
+    Ok(target as usize)
+}
+
+// Whether a jump is always (`Some(true)`) or never (`Some(false)`) taken. A jump on
+// an immediate value always goes the same way, and the other branch is often data
+// rather than code.
+fn jumps(instruction: &Decoded) -> Option<bool> {
+    match instruction.params[0] {
+        Param::Immediate(value) => Some((value != 0) == (instruction.number == 5)),
+        _ => None,
+    }
+}
+
+// Decodes every instruction reachable from position 0, and checks that nothing
+// reachable can change the code or the jump targets while the program runs.
+fn analyze(code: &[i64]) -> Result<BTreeMap<usize, Decoded>, TranspileError> {
+    let mut decoded = BTreeMap::new();
+    let mut pending = vec![0];
+
+    // Positions that didn't decode, and why. Reported only if nothing explains
+    // them better, since garbage an instruction writes over is self-modification.
+    let mut failures = BTreeMap::new();
+
+    while let Some(pos) = pending.pop() {
+        if decoded.contains_key(&pos) || failures.contains_key(&pos) {
+            continue;
+        }
+
+        let instruction = match decode(code, pos) {
+            Ok(instruction) => instruction,
+            Err(e) => {
+                failures.insert(pos, e);
+                continue;
+            }
+        };
+
+        match instruction.number {
+            99 => {}
+            5 | 6 => {
+                let jumps = jumps(&instruction);
+
+                if jumps != Some(true) {
+                    pending.push(instruction.next());
+                }
+                if jumps != Some(false) {
+                    match jump_target(code, &instruction) {
+                        Ok(target) => pending.push(target),
+                        Err(e) => {
+                            failures.insert(pos, e);
+                        }
+                    }
+                }
+            }
+            _ => pending.push(instruction.next()),
+        }
+
+        decoded.insert(pos, instruction);
+    }
+
+    let code_words = decoded
+        .values()
+        .flat_map(|instruction| instruction.pos..instruction.next())
+        .chain(failures.keys().cloned())
+        .collect::<HashSet<_>>();
+
+    let mut written = HashSet::new();
+
+    for instruction in decoded.values() {
+        match instruction.write_param() {
+            Some(Param::Position(target)) if code_words.contains(&target) => {
+                return Err(TranspileError::SelfModifying {
+                    pos: instruction.pos,
+                    target,
+                });
+            }
+            Some(Param::Position(target)) => {
+                written.insert(target);
+            }
+            Some(Param::Relative(_)) => {
+                return Err(TranspileError::DynamicWrite {
+                    pos: instruction.pos,
+                });
+            }
+            _ => {}
+        }
+    }
+
+    if let Some((_, e)) = failures.into_iter().next() {
+        return Err(e);
+    }
+
+    for instruction in decoded.values() {
+        if (instruction.number != 5 && instruction.number != 6)
+            || jumps(instruction) == Some(false)
+        {
+            continue;
+        }
+
+        if let Param::Position(index) = instruction.params[1] {
+            if written.contains(&index) {
+                return Err(TranspileError::DynamicJump {
+                    pos: instruction.pos,
+                });
+            }
+        }
+    }
+
+    Ok(decoded)
+}
+
+fn read(param: Param) -> String {
+    match param {
+        Param::Position(index) => format!("get(&mem, {})", index),
+        Param::Immediate(value) if value < 0 => format!("({})", value),
+        Param::Immediate(value) => value.to_string(),
+        Param::Relative(offset) => format!("get(&mem, addr(rb + ({})))", offset),
+    }
+}
+
+// Immediate-mode writes are ignored, which is what the interpreter does.
+fn write(param: Param, value: &str) -> String {
+    match param {
+        Param::Position(index) => {
+            format!("let value = {}; set(&mut mem, {}, value);", value, index)
+        }
+        _ => String::new(),
+    }
+}
+
+fn statement(code: &[i64], instruction: &Decoded) -> String {
+    let p = &instruction.params;
+    let next = instruction.next();
+
+    match instruction.number {
+        1 => format!(
+            "{} pc = {};",
+            write(p[2], &format!("{} + {}", read(p[0]), read(p[1]))),
+            next
+        ),
+        2 => format!(
+            "{} pc = {};",
+            write(p[2], &format!("{} * {}", read(p[0]), read(p[1]))),
+            next
+        ),
+        3 => match p[0] {
+            Param::Position(_) => format!(
+                "match input.next() {{ Some(value) => {{ {} }} None => return output }} \
+                 pc = {};",
+                write(p[0], "value"),
+                next
+            ),
+            _ => format!(
+                "if input.next().is_none() {{ return output; }} pc = {};",
+                next
+            ),
+        },
+        4 => format!("output.push({}); pc = {};", read(p[0]), next),
+        // The target of a jump that is never taken was never checked.
+        5 | 6 if jumps(instruction) == Some(false) => format!("pc = {};", next),
+        5 | 6 => format!(
+            "pc = if {} {} 0 {{ {} }} else {{ {} }};",
+            read(p[0]),
+            if instruction.number == 5 { "!=" } else { "==" },
+            jump_target(code, instruction).unwrap(),
+            next
+        ),
+        7 | 8 => format!(
+            "{} pc = {};",
+            write(
+                p[2],
+                &format!(
+                    "if {} {} {} {{ 1 }} else {{ 0 }}",
+                    read(p[0]),
+                    if instruction.number == 7 { "<" } else { "==" },
+                    read(p[1])
+                )
+            ),
+            next
+        ),
+        9 => format!("rb += {}; pc = {};", read(p[0]), next),
+        _ => "return output;".to_string(),
+    }
+}
+
+fn memory_literal(code: &[i64]) -> String {
+    code.chunks(16)
+        .map(|chunk| {
+            let values = chunk
+                .iter()
+                .map(|value| value.to_string())
+                .collect::<Vec<_>>();
+            format!("        {},", values.join(", "))
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+const HEADER: &str = "// Generated by adventofcode2019rust::transpiler. Do not edit.\n";
+
+const NATIVE_PRELUDE: &str = r#"#[allow(unused_mut, unused_variables, dead_code, clippy::all)]
+pub fn run(input: &mut impl Iterator<Item = i64>) -> Vec<i64> {
+    fn get(mem: &[i64], index: usize) -> i64 {
+        mem.get(index).copied().unwrap_or(0)
+    }
+    fn set(mem: &mut Vec<i64>, index: usize, value: i64) {
+        if index >= mem.len() {
+            mem.resize(index + 1, 0);
+        }
+        mem[index] = value;
+    }
+    fn addr(index: i64) -> usize {
+        assert!(index >= 0, "negative address {}", index);
+        index as usize
+    }
+"#;
+
+/**
+ * Translates a program into Rust source for an equivalent
+ * `pub fn run(input: &mut impl Iterator<Item = i64>) -> Vec<i64>`.
+ *
+ * Only programs that can be shown not to modify their own code are supported.
+ */
+pub fn transpile(code: &[i64]) -> Result<String, TranspileError> {
+    let decoded = analyze(code)?;
+
+    let mut source = String::new();
+
+    source.push_str(HEADER);
+    source.push_str(NATIVE_PRELUDE);
+    writeln!(source, "    let mut mem: Vec<i64> = vec![").unwrap();
+    writeln!(source, "{}", memory_literal(code)).unwrap();
+    writeln!(source, "    ];").unwrap();
+    writeln!(source, "    let mut output = Vec::new();").unwrap();
+    writeln!(source, "    let mut rb: i64 = 0;").unwrap();
+    writeln!(source, "    let mut pc: usize = 0;").unwrap();
+    writeln!(source, "    loop {{").unwrap();
+    writeln!(source, "        match pc {{").unwrap();
+
+    for instruction in decoded.values() {
+        writeln!(
+            source,
+            "            {} => {{ {} }}",
+            instruction.pos,
+            statement(code, instruction)
+        )
+        .unwrap();
+    }
+
+    writeln!(
+        source,
+        "            _ => unreachable!(\"no instruction at {{}}\", pc),"
+    )
+    .unwrap();
+    writeln!(source, "        }}").unwrap();
+    writeln!(source, "    }}").unwrap();
+    writeln!(source, "}}").unwrap();
+
+    Ok(source)
+}
+
+/**
+ * Like `transpile`, but programs that can't be translated get a `run` that hands
+ * the work to `program::run_program_with_input_iter` instead.
+ */
+pub fn transpile_or_fallback(code: &[i64]) -> String {
+    let reason = match transpile(code) {
+        Ok(source) => return source,
+        Err(e) => e,
+    };
+
+    let mut source = String::new();
+
+    source.push_str(HEADER);
+    writeln!(source, "// Falls back to the interpreter: {}.", reason).unwrap();
+    writeln!(
+        source,
+        "pub fn run(input: &mut impl Iterator<Item = i64>) -> Vec<i64> {{"
+    )
+    .unwrap();
+    writeln!(source, "    const CODE: &[i64] = &[").unwrap();
+    writeln!(source, "{}", memory_literal(code)).unwrap();
+    writeln!(source, "    ];").unwrap();
+    writeln!(
+        source,
+        "    adventofcode2019rust::program::run_program_with_input_iter(CODE, input)"
+    )
+    .unwrap();
+    writeln!(source, "        .all_output()").unwrap();
+    writeln!(source, "        .to_vec()").unwrap();
+    writeln!(source, "}}").unwrap();
+
+    source
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::program;
+    use std::env;
+    use std::fs;
+    use std::process::Command;
+
+    fn parse(text: &str) -> Vec<i64> {
+        text.trim()
+            .split(',')
+            .map(|node| node.parse::<i64>().unwrap())
+            .collect()
+    }
+
+    // Each case is a program and the inputs to run it with.
+    fn cases() -> Vec<(Vec<i64>, Vec<Vec<i64>>)> {
+        vec![
+            (
+                vec![3, 9, 8, 9, 10, 9, 4, 9, 99, -1, 8],
+                vec![vec![7], vec![8], vec![]],
+            ),
+            (
+                vec![3, 12, 6, 12, 15, 1, 13, 14, 13, 4, 13, 99, -1, 0, 1, 9],
+                vec![vec![0], vec![1]],
+            ),
+            (
+                vec![
+                    3, 21, 1008, 21, 8, 20, 1005, 20, 22, 107, 8, 21, 20, 1006, 20, 31,
+                    1106, 0, 36, 98, 0, 0, 1002, 21, 125, 20, 4, 20, 1105, 1, 46, 104,
+                    999, 1105, 1, 46, 1101, 1000, 1, 20, 4, 20, 1105, 1, 46, 98, 99,
+                ],
+                vec![vec![7], vec![8], vec![9]],
+            ),
+            (vec![104, 1_125_899_906_842_624, 99], vec![vec![]]),
+            // Sums 1..=n for each input n.
+            (
+                vec![
+                    3, 100, 1101, 0, 1, 102, 1101, 0, 0, 101, 7, 100, 102, 103, 1005,
+                    103, 30, 1, 101, 102, 101, 101, 1, 102, 102, 1105, 1, 10, 99, 99, 4,
+                    101, 1105, 1, 0,
+                ],
+                vec![vec![4, 10, 0], vec![]],
+            ),
+            (vec![109, 5, 204, 1, 99, 0, 77], vec![vec![]]),
+            // Jumps that are never taken, to targets that couldn't be.
+            (vec![1105, 0, -5, 104, 1, 99], vec![vec![]]),
+            (vec![2105, 0, 0, 99], vec![vec![]]),
+        ]
+    }
+
+    fn rustc() -> String {
+        env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string())
+    }
+
+    #[test]
+    fn test_transpile_rejects_self_modifying_code() {
+        assert_eq!(
+            transpile(&[1, 0, 0, 0, 99]),
+            Err(TranspileError::SelfModifying { pos: 0, target: 0 })
+        );
+        assert_eq!(
+            transpile(&[3, 3, 1105, 0, 0, 99]),
+            Err(TranspileError::SelfModifying { pos: 0, target: 3 })
+        );
+    }
+
+    #[test]
+    fn test_transpile_rejects_dynamic_jumps_and_writes() {
+        assert_eq!(
+            transpile(&[3, 7, 5, 7, 7, 99, 99, 5]),
+            Err(TranspileError::DynamicJump { pos: 2 })
+        );
+        assert_eq!(
+            transpile(&[203, 10, 99]),
+            Err(TranspileError::DynamicWrite { pos: 0 })
+        );
+        assert_eq!(
+            transpile(&[42, 99]),
+            Err(TranspileError::InvalidOpcode { pos: 0, value: 42 })
+        );
+    }
+
+    #[test]
+    fn test_transpile_ignores_jumps_never_taken() {
+        for code in &[vec![1105, 0, -5, 99], vec![2105, 0, 0, 99]] {
+            let source = transpile(code).unwrap();
+
+            assert!(source.contains("0 => { pc = 3; }"), "{:?}", code);
+            assert_eq!(transpile_or_fallback(code), source);
+        }
+
+        assert_eq!(
+            transpile(&[1105, 1, -5, 99]),
+            Err(TranspileError::NegativeAddress { pos: 0 })
+        );
+    }
+
+    #[test]
+    fn test_transpile_day_05_patches_itself() {
+        let d05 = parse(include_str!("../data/d05.txt"));
+
+        assert_eq!(
+            transpile(&d05),
+            Err(TranspileError::SelfModifying { pos: 2, target: 6 })
+        );
+    }
+
+    #[test]
+    fn test_transpile_or_fallback() {
+        let d07 = parse(include_str!("../data/d07.txt"));
+        let d09 = parse(include_str!("../data/d09.txt"));
+
+        assert!(transpile(&d07).is_err());
+        assert!(transpile(&d09).is_err());
+
+        let source = transpile_or_fallback(&d09);
+        assert!(source.contains("run_program_with_input_iter(CODE, input)"));
+        assert!(!source.contains("match pc"));
+
+        let source = transpile_or_fallback(&[104, 7, 99]);
+        assert!(source.contains("match pc"));
+    }
+
+    #[test]
+    fn test_transpile_matches_interpreter() {
+        let cases = cases();
+
+        let dir = env::temp_dir().join(format!("transpiler-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let mut main = String::new();
+
+        for (i, (code, _)) in cases.iter().enumerate() {
+            let source = transpile(code).expect("expected a native translation");
+            writeln!(main, "mod p{} {{\n{}}}", i, source).unwrap();
+        }
+
+        writeln!(main, "fn main() {{").unwrap();
+        writeln!(main, "    let args: Vec<i64> = std::env::args()").unwrap();
+        writeln!(main, "        .skip(1)").unwrap();
+        writeln!(main, "        .map(|arg| arg.parse().unwrap())").unwrap();
+        writeln!(main, "        .collect();").unwrap();
+        writeln!(main, "    let mut input = args[1..].iter().cloned();").unwrap();
+        writeln!(main, "    let output = match args[0] {{").unwrap();
+        for i in 0..cases.len() {
+            writeln!(main, "        {} => p{}::run(&mut input),", i, i).unwrap();
+        }
+        writeln!(main, "        _ => unreachable!(),").unwrap();
+        writeln!(main, "    }};").unwrap();
+        writeln!(main, "    println!(\"{{:?}}\", output);").unwrap();
+        writeln!(main, "}}").unwrap();
+
+        let main_path = dir.join("main.rs");
+        let binary = dir.join("transpiled");
+        fs::write(&main_path, main).unwrap();
+
+        let status = Command::new(rustc())
+            .arg("--edition=2018")
+            .arg("-o")
+            .arg(&binary)
+            .arg(&main_path)
+            .status()
+            .expect("could not run rustc");
+        assert!(status.success());
+
+        for (i, (code, inputs)) in cases.iter().enumerate() {
+            for input in inputs {
+                let expected = program::run_program_with_input_iter(
+                    code,
+                    &mut input.iter().cloned(),
+                );
+
+                let result = Command::new(&binary)
+                    .arg(i.to_string())
+                    .args(input.iter().map(|value| value.to_string()))
+                    .output()
+                    .unwrap();
+                assert!(result.status.success());
+
+                assert_eq!(
+                    String::from_utf8(result.stdout).unwrap().trim(),
+                    format!("{:?}", expected.all_output()),
+                    "program {} with input {:?}",
+                    i,
+                    input
+                );
+            }
+        }
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}